@@ -0,0 +1,160 @@
+use bevy::{audio::PlaybackMode, prelude::*, state::state::StateTransitionEvent};
+
+use super::{pause::PauseState, settings::Settings, AppState, GameAssets};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Callout {
+    LevelStart,
+    SpySighted,
+    ConditionRed,
+    FormationAttack,
+}
+
+impl Callout {
+    fn handle(&self, game_assets: &GameAssets) -> Handle<AudioSource> {
+        match self {
+            Callout::LevelStart => game_assets.blast_off_voice.clone(),
+            Callout::SpySighted => game_assets.spy_ship_sighted_voice.clone(),
+            Callout::ConditionRed => game_assets.condition_red_voice.clone(),
+            Callout::FormationAttack => game_assets.battle_stations_voice.clone(),
+        }
+    }
+
+    // higher goes first when more than one is waiting
+    fn priority(&self) -> u8 {
+        match self {
+            Callout::LevelStart => 0,
+            Callout::SpySighted => 1,
+            Callout::FormationAttack => 2,
+            Callout::ConditionRed => 3,
+        }
+    }
+
+    // seconds it can wait before it's no longer worth saying
+    fn shelf_life(&self) -> f32 {
+        match self {
+            Callout::LevelStart => 1.0,
+            Callout::SpySighted | Callout::FormationAttack => 2.0,
+            Callout::ConditionRed => 4.0,
+        }
+    }
+}
+
+#[derive(Message)]
+pub struct Announce(pub Callout);
+
+// one at a time, the rest wait their turn in `Announcer`
+#[derive(Component)]
+struct Announcement;
+
+#[derive(Resource, Default)]
+struct Announcer {
+    // with the game time it was asked for
+    queue: Vec<(Callout, f32)>,
+}
+
+pub struct AnnouncerPlugin;
+impl Plugin for AnnouncerPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_message::<Announce>()
+            .init_resource::<Announcer>()
+            .add_systems(
+                Update,
+                (
+                    clear_announcements,
+                    // nothing new starts behind the pause menu
+                    announce.run_if(not(in_state(PauseState::Paused))),
+                    hold_announcement,
+                )
+                    .chain(),
+            );
+    }
+}
+
+// nothing carries over into the next screen or restart
+fn clear_announcements(
+    mut commands: Commands,
+    mut transitions: MessageReader<StateTransitionEvent<AppState>>,
+    mut announcer: ResMut<Announcer>,
+    q_playing: Query<Entity, With<Announcement>>,
+) {
+    if transitions.read().count() == 0 {
+        return;
+    }
+
+    announcer.queue.clear();
+    for ent in &q_playing {
+        commands.entity(ent).despawn();
+    }
+}
+
+// game time, so nothing goes stale behind the pause menu
+fn announce(
+    mut commands: Commands,
+    time: Res<Time>,
+    game_assets: Option<Res<GameAssets>>,
+    settings: Res<Settings>,
+    mut events: MessageReader<Announce>,
+    mut announcer: ResMut<Announcer>,
+    q_playing: Query<(), With<Announcement>>,
+) {
+    let now = time.elapsed_secs();
+    for Announce(callout) in events.read() {
+        // already waiting, it'd only be said twice
+        if !announcer.queue.iter().any(|(queued, _)| queued == callout) {
+            announcer.queue.push((*callout, now));
+        }
+    }
+
+    announcer
+        .queue
+        .retain(|(callout, asked)| now - asked <= callout.shelf_life());
+
+    let Some(game_assets) = game_assets else {
+        return;
+    };
+    if !q_playing.is_empty() {
+        return;
+    }
+
+    // the most important, and the oldest of those
+    let Some(next) = announcer
+        .queue
+        .iter()
+        .enumerate()
+        .max_by(|(_, (a, a_asked)), (_, (b, b_asked))| {
+            a.priority()
+                .cmp(&b.priority())
+                .then(b_asked.total_cmp(a_asked))
+        })
+        .map(|(i, _)| i)
+    else {
+        return;
+    };
+
+    let (callout, _) = announcer.queue.remove(next);
+    commands.spawn((
+        AudioPlayer::new(callout.handle(&game_assets)),
+        PlaybackSettings {
+            mode: PlaybackMode::Despawn,
+            volume: settings.sfx_volume(),
+            ..default()
+        },
+        Announcement,
+    ));
+}
+
+// the clip that's playing waits behind the pause menu too
+fn hold_announcement(
+    pause_state: Option<Res<State<PauseState>>>,
+    q_playing: Query<&AudioSink, With<Announcement>>,
+) {
+    let paused = pause_state.is_some_and(|state| *state.get() == PauseState::Paused);
+    for sink in &q_playing {
+        if paused {
+            sink.pause();
+        } else {
+            sink.play();
+        }
+    }
+}
@@ -0,0 +1,38 @@
+// Turns the level maps in assets/levels into the .level.ron files the game
+// loads, see levels::level_from_png for the map format.
+//
+//   cargo run --bin build_levels
+
+use bevy::prelude::BevyError;
+use bosconian::levels;
+use std::{fs, path::Path};
+
+fn main() -> Result<(), BevyError> {
+    let dir = Path::new("assets/levels");
+
+    let mut maps = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().is_some_and(|ext| ext == "png") {
+            maps.push(path);
+        }
+    }
+    maps.sort();
+
+    for path in maps {
+        let file_name = path.file_name().unwrap().to_string_lossy();
+        let (n, mut level) = levels::level_from_png(&file_name, &fs::read(&path)?)?;
+
+        // formations can't be drawn on a map, keep whatever the old file had
+        let out = dir.join(format!("level_{}.level.ron", n));
+        if let Ok(old) = fs::read(&out) {
+            let old: levels::Level = ron::de::from_bytes(&old)?;
+            level.formations = old.formations;
+        }
+
+        fs::write(&out, levels::level_to_ron(&level)?)?;
+        println!("{} -> {}", file_name, out.display());
+    }
+
+    Ok(())
+}
@@ -1,35 +1,53 @@
 use bevy::{
     color::palettes::css::*,
     prelude::*,
-    camera::{Viewport, visibility::RenderLayers}
+    camera::{Viewport, visibility::RenderLayers},
+    sprite::Anchor,
 };
 use bevy_prototype_lyon::prelude::*;
 use bevy_rapier2d::prelude::*;
-use rand::{thread_rng, Rng};
-use std::{f32::consts::PI, time::Duration};
+use rand::Rng;
+use std::{collections::HashSet, f32::consts::PI, time::Duration};
 
 use super::{
+    announcer::{Announce, Callout},
     game::{
-        animation, bullet_timer, button_system, despawn_finished_sound_effects, destroy_game,
-        follow_camera, listen_explosion, move_enemy_ships, player_input, star_node_shoot,
-        update_minimap, world_to_minimap, Animation, CameraOffset, Collidable, Countdown,
-        CountdownText, EnemyShip, EnemyType, Explodable, ExplodableType, ExplosionEvent,
-        ExplosionSize, GameButton, GameButtonAction, GameCamera, GameNode, IType, LevelNode,
-        MinimapCamera, MinimapPlayer, MinimapStar, PType, Player, PlayerDeathEvent, SetupLevel,
-        StarCore, StarNode,
+        animation, bullet_timer, button_system, destroy_game,
+        follow_camera, listen_explosion, move_enemy_ships, move_missiles, player_input,
+        spawn_rock, spawn_star, star_hatch, star_node_shoot, update_minimap, world_to_minimap,
+        Animation, CameraOffset, Collidable, Countdown, CountdownText, EnemyShip, EnemyType,
+        Explodable, ExplodableType, ExplosionEvent, ExplosionSize, GameButton, GameButtonAction,
+        GameCamera, GameNode, Hatch, HatchState, IType, LevelNode, MinimapCamera, MinimapPlayer,
+        MINIMAP_SCALE, MINIMAP_SIZE,
+        KillEvent, PType, Player, PlayerDeathEvent, PlayerProjectile, SetupLevel, StarCore,
+        StarNode,
     },
-    levels, AppState, Atlas, GameAssets,
+    formation::{move_escorts, spawn_formation, Formation, FormationMember},
+    powerup::{
+        collect_power_ups, maybe_drop_power_up, spawn_power_up_hud, update_power_ups, PowerUps,
+    },
+    highscores::{spawn_initials_entry, HighScores, Mode, PendingScore},
+    levels::{GameLevels, Playtest},
+    pause::{toggle_pause, PauseState},
+    music::{MusicCue, Track},
+    sfx::{Sfx, SfxEvent},
+    AppState, GameAssets,
 };
 
-struct Level {
-    stars: Vec<levels::Star>,
-    rocks: Vec<levels::Rock>,
-    start_i: usize,
-    max_i: usize,
-    start_p: usize,
-    max_p: usize,
-    time_limit: usize,
-}
+// shooting the core directly
+const STAR_CORE_POINTS: usize = 1500;
+// core goes down on its own after all six nodes are shot
+const STAR_PEELED_POINTS: usize = 1000;
+// all stars destroyed before Red Alert
+const NO_RED_ALERT_BONUS: usize = 2000;
+// every ship in a formation shot down
+const FORMATION_BONUS: usize = 1000;
+// shooting the spy before it gets away
+const SPY_BONUS: usize = 1500;
+// spy turns around once it gets this close to the player
+const SPY_SIGHT_DISTANCE: f32 = 400.0;
+// and calls Red Alert if it gets a minimap's radius away again
+const SPY_ESCAPE_DISTANCE: f32 = MINIMAP_SIZE / 2.0 * MINIMAP_SCALE;
 
 #[derive(Component)]
 struct LevelText;
@@ -40,6 +58,14 @@ struct Lives;
 #[derive(Component)]
 struct RedAlert;
 
+#[derive(Component)]
+struct ScoreText;
+
+#[derive(Component)]
+struct Spy {
+    seen_player: bool,
+}
+
 #[derive(Component)]
 struct SetupTimer(Timer);
 
@@ -49,19 +75,39 @@ struct ITypeTimer(Timer);
 #[derive(Message)]
 struct UpdateLivesEvent;
 
+// when the player earns another ship, the first at `first`
+// points and then every `every` points after that
 #[derive(Resource)]
-struct Levels(Vec<Level>);
+struct ExtraLives {
+    first: usize,
+    every: usize,
+    max_lives: usize,
+}
+
+impl Default for ExtraLives {
+    fn default() -> Self {
+        ExtraLives {
+            first: 20_000,
+            every: 70_000,
+            max_lives: 6,
+        }
+    }
+}
 
 #[derive(Resource)]
 struct Game {
     level: usize,
     lives: usize,
+    score: usize,
+    next_extra_life: usize,
     countdown: usize,
     setup: bool,
     level_start_seconds: f32,
+    formations_spawned: usize,
     red_alert: bool,
     itype_timer: Timer,
     ptype_timer: Timer,
+    spy_timer: Timer,
 }
 
 #[derive(Clone, Copy, Default, Eq, PartialEq, Debug, Hash, States)]
@@ -80,10 +126,20 @@ impl Plugin for ClassicPlugin {
         app.insert_state(ClassicGameState::None)
             .add_message::<ExplosionEvent>()
             .add_message::<PlayerDeathEvent>()
+            .add_message::<KillEvent>()
             .add_message::<UpdateLivesEvent>()
             .add_message::<SetupLevel>()
+            .init_resource::<ExtraLives>()
             .add_systems(OnEnter(AppState::Classic), setup_game)
             .add_systems(OnExit(AppState::Classic), destroy_game)
+            // Restart from the pause menu, OnExit and OnEnter don't run going from Classic to Classic
+            .add_systems(
+                OnTransition {
+                    exited: AppState::Classic,
+                    entered: AppState::Classic,
+                },
+                (destroy_game, setup_game).chain(),
+            )
             .add_systems(OnEnter(ClassicGameState::GameOver), setup_gameover)
             .add_systems(
                 Update,
@@ -93,27 +149,51 @@ impl Plugin for ClassicPlugin {
                 Update,
                 countdown.run_if(in_state(ClassicGameState::Countdown)),
             )
+            .add_systems(
+                Update,
+                toggle_pause.run_if(
+                    in_state(ClassicGameState::Countdown).or(in_state(ClassicGameState::Play)),
+                ),
+            )
             .add_systems(
                 Update,
                 (
                     player_input,
                     follow_camera,
                     check_collisions,
+                    // after the smart bomb too, and before a finished formation is paid out
+                    score_kills
+                        .after(check_collisions)
+                        .after(collect_power_ups)
+                        .before(formation_bonus),
                     bullet_timer,
                     spawn_enemy_ships,
+                    spawn_formations,
+                    spawn_spy,
+                    spy_update.before(move_enemy_ships),
                     move_enemy_ships,
+                    move_escorts,
+                    formation_bonus,
                     star_node_shoot,
+                    star_hatch,
+                    move_missiles,
                     star_update,
                     update_minimap,
-                    despawn_finished_sound_effects,
                 )
-                    .run_if(in_state(ClassicGameState::Play)),
+                    .run_if(in_state(ClassicGameState::Play).and(in_state(PauseState::Running))),
+            )
+            .add_systems(
+                Update,
+                (collect_power_ups, update_power_ups)
+                    .run_if(in_state(ClassicGameState::Play).and(in_state(PauseState::Running))),
             )
             .add_systems(
                 Update,
                 (
                     animation,
                     listen_update_lives,
+                    update_score,
+                    award_extra_lives,
                     listen_player_death_classic,
                     listen_explosion,
                 )
@@ -130,6 +210,7 @@ fn setup_game(
     mut commands: Commands,
     mut rapier_config: Query<&mut RapierConfiguration>,
     game_assets: Res<GameAssets>,
+    extra_lives: Res<ExtraLives>,
     mut game_state: ResMut<NextState<ClassicGameState>>,
     mut level_events: MessageWriter<SetupLevel>,
 ) {
@@ -236,6 +317,24 @@ fn setup_game(
         GameNode,
     ));
 
+    // score, drawn by the lives camera under the level text
+    commands.spawn((
+        Text2d::new("Score 0"),
+        TextColor(Color::WHITE),
+        TextFont {
+            font: game_assets.font.clone(),
+            font_size: 20.0,
+            ..default()
+        },
+        Anchor::TOP_LEFT,
+        Transform::from_xyz(-(1000.0 / 2.0) + 15.0, (750.0 / 2.0) - 45.0, 0.0),
+        ScoreText,
+        RenderLayers::layer(2),
+        GameNode,
+    ));
+
+    spawn_power_up_hud(&mut commands, &game_assets);
+
     // minimap player
     commands.spawn((
         ShapeBuilder::with(&shapes::Circle {
@@ -254,44 +353,18 @@ fn setup_game(
     commands.insert_resource(Game {
         level: 0,
         lives: 4,
+        score: 0,
+        next_extra_life: extra_lives.first,
         countdown: 3,
         setup: false,
         level_start_seconds: 0.0,
+        formations_spawned: 0,
         red_alert: false,
         itype_timer: Timer::from_seconds(2.0, TimerMode::Repeating),
         ptype_timer: Timer::from_seconds(2.0, TimerMode::Repeating),
+        spy_timer: Timer::from_seconds(20.0, TimerMode::Repeating),
     });
 
-    commands.insert_resource(Levels(vec![
-        Level {
-            stars: levels::LEVEL_1.stars.to_vec(),
-            rocks: levels::LEVEL_1.rocks.to_vec(),
-            start_i: levels::LEVEL_1.start_i,
-            max_i: levels::LEVEL_1.max_i,
-            start_p: levels::LEVEL_1.start_p,
-            max_p: levels::LEVEL_1.max_p,
-            time_limit: levels::LEVEL_1.time_limit,
-        },
-        Level {
-            stars: levels::LEVEL_2.stars.to_vec(),
-            rocks: levels::LEVEL_2.rocks.to_vec(),
-            start_i: levels::LEVEL_2.start_i,
-            max_i: levels::LEVEL_2.max_i,
-            start_p: levels::LEVEL_2.start_p,
-            max_p: levels::LEVEL_2.max_p,
-            time_limit: levels::LEVEL_2.time_limit,
-        },
-        Level {
-            stars: levels::LEVEL_3.stars.to_vec(),
-            rocks: levels::LEVEL_3.rocks.to_vec(),
-            start_i: levels::LEVEL_3.start_i,
-            max_i: levels::LEVEL_3.max_i,
-            start_p: levels::LEVEL_3.start_p,
-            max_p: levels::LEVEL_3.max_p,
-            time_limit: levels::LEVEL_3.time_limit,
-        },
-    ]));
-
     commands.spawn((
         Countdown(Timer::from_seconds(0.01, TimerMode::Repeating)),
         GameNode,
@@ -309,11 +382,24 @@ fn setup_gameover(
     mut commands: Commands,
     game_assets: Res<GameAssets>,
     game: Res<Game>,
+    levels: GameLevels,
+    playtest: Option<Res<Playtest>>,
+    high_scores: Res<HighScores>,
+    mut music_cue: ResMut<MusicCue>,
     q_camera: Query<&mut Transform, With<GameCamera>>,
 ) {
+    music_cue.0 = Some(Track::GameOver);
+
+    // playtesting doesn't count
+    let new_high_score = playtest.is_none() && high_scores.qualifies(Mode::Classic, game.score);
+    if new_high_score {
+        let level = game.level.min(levels.len());
+        commands.insert_resource(PendingScore::new(Mode::Classic, game.score, level));
+    }
+
     let camera = q_camera.single().unwrap();
     let trans = camera.translation + Vec3::new(0.0, 100.0, -50.0);
-    let texture = if game.level > levels::MAX_LEVEL {
+    let texture = if game.level > levels.len() {
         game_assets.you_won.clone()
     } else {
         game_assets.game_over.clone()
@@ -346,6 +432,20 @@ fn setup_gameover(
             GameNode,
         ))
         .with_children(|parent| {
+            parent.spawn((
+                Text::new(format!("Score {}", game.score)),
+                TextColor(Color::srgb(0.9, 0.9, 0.9)),
+                TextFont {
+                    font: game_assets.font.clone(),
+                    font_size: 30.0,
+                    ..default()
+                },
+            ));
+
+            if new_high_score {
+                spawn_initials_entry(parent, &game_assets);
+            }
+
             parent
                 .spawn((
                     Button,
@@ -375,12 +475,46 @@ fn setup_gameover(
                         },
                     ));
                 });
+
+            // back to the level that was being playtested
+            if playtest.is_some() {
+                parent
+                    .spawn((
+                        Button,
+                        Node {
+                            width: Val::Px(225.0),
+                            height: Val::Px(70.0),
+                            justify_content: JustifyContent::Center,
+                            align_items: AlignItems::Center,
+                            ..default()
+                        },
+                        BorderRadius::all(Val::Px(10.0)),
+                        BackgroundColor(Color::BLACK),
+                        GameButton {
+                            action: GameButtonAction::ReturnToEditor,
+                            idle_color: Color::srgb(0.15, 0.15, 0.15),
+                            hover_color: Color::srgb(0.25, 0.25, 0.25),
+                        },
+                    ))
+                    .with_children(|parent| {
+                        parent.spawn((
+                            Text::new("Editor"),
+                            TextColor(Color::srgb(0.9, 0.9, 0.9)),
+                            TextFont {
+                                font: game_assets.font.clone(),
+                                font_size: 30.0,
+                                ..default()
+                            },
+                        ));
+                    });
+            }
         });
 }
 
 fn setup_level(
     mut level_event: MessageReader<SetupLevel>,
     mut game: ResMut<Game>,
+    levels: GameLevels,
     mut game_state: ResMut<NextState<ClassicGameState>>,
     mut minimap: Query<&mut Camera, With<MinimapCamera>>,
     mut q_mm_player: Query<&mut Transform, With<MinimapPlayer>>,
@@ -388,6 +522,7 @@ fn setup_level(
     for _ in level_event.read() {
         game.level += 1;
         game.setup = true;
+        game.formations_spawned = 0;
 
         let mut cam = minimap.single_mut().unwrap();
         cam.is_active = false;
@@ -396,7 +531,7 @@ fn setup_level(
             mm_trans.translation = world_to_minimap(Vec3::ZERO);
         }
 
-        if game.level > levels::MAX_LEVEL {
+        if game.level > levels.len() {
             game_state.set(ClassicGameState::GameOver);
         } else {
             game_state.set(ClassicGameState::Countdown);
@@ -407,7 +542,7 @@ fn setup_level(
 fn countdown(
     mut commands: Commands,
     time: Res<Time>,
-    levels: Res<Levels>,
+    levels: GameLevels,
     game_assets: Res<GameAssets>,
     mut game: ResMut<Game>,
     mut game_state: ResMut<NextState<ClassicGameState>>,
@@ -431,13 +566,14 @@ fn countdown(
                 // undo Red Alert by
                 // 1. setting to false and removing the flashing sprite
                 game.red_alert = false;
+                commands.insert_resource(MusicCue(None));
                 for ent in q_red_alert.iter() {
                     commands.entity(ent).despawn();
                 }
 
                 // 2. removing ships that were spawned during Red Alert
                 // that are above the "normal limit"
-                let level = &levels.0[game.level - 1];
+                let level = levels.get(game.level).unwrap();
 
                 let mut i_count = 0;
                 for _ in q_i_type.iter() {
@@ -484,13 +620,14 @@ fn countdown(
                         one_time: false,
                     },
                     Player,
+                    PowerUps::default(),
                     RigidBody::Dynamic,
                     Collider::ball(28.0),
                     Ccd::enabled(),
                     Sensor,
                     CollisionGroups::new(
                         Group::from_bits_truncate(0b00000001),
-                        Group::from_bits_truncate(0b11001110),
+                        Group::from_bits_truncate(0b1111001110),
                     ),
                     CameraOffset(Vec3::ZERO),
                     Velocity::default(),
@@ -502,163 +639,12 @@ fn countdown(
                 if game.setup {
                     game.setup = false;
 
-                    let level = &levels.0[game.level - 1];
-
                     for star in &level.stars {
-                        let marker = commands
-                            .spawn((
-                                ShapeBuilder::with(&shapes::Circle {
-                                    radius: 7f32,
-                                    center: Vec2::ZERO,
-                                })
-                                .fill(Color::srgb(0f32, 0.741, 0f32))
-                                .build(),
-                                Transform {
-                                    translation: world_to_minimap(Vec3::new(star.x, star.y, 3.0)),
-                                    ..default()
-                                },
-                                MinimapStar,
-                                RenderLayers::layer(1),
-                                LevelNode,
-                                GameNode,
-                            ))
-                            .id();
-
-                        let star_config: (Handle<Image>, [(Vec3, Atlas); 6]) = if star.vert {
-                            (
-                                game_assets.v_star.clone(),
-                                [
-                                    (Vec3::new(48.0, 96.0, 1.0), game_assets.star_node_v1.clone()),
-                                    (Vec3::new(112.0, 0.0, 1.0), game_assets.star_node_v2.clone()),
-                                    (
-                                        Vec3::new(48.0, -96.0, 1.0),
-                                        game_assets.star_node_v3.clone(),
-                                    ),
-                                    (
-                                        Vec3::new(-48.0, -96.0, 1.0),
-                                        game_assets.star_node_v4.clone(),
-                                    ),
-                                    (
-                                        Vec3::new(-112.0, 0.0, 1.0),
-                                        game_assets.star_node_v5.clone(),
-                                    ),
-                                    (
-                                        Vec3::new(-48.0, 96.0, 1.0),
-                                        game_assets.star_node_v6.clone(),
-                                    ),
-                                ],
-                            )
-                        } else {
-                            (
-                                game_assets.h_star.clone(),
-                                [
-                                    (
-                                        Vec3::new(96.0, -48.0, 1.0),
-                                        game_assets.star_node_h1.clone(),
-                                    ),
-                                    (
-                                        Vec3::new(0.0, -112.0, 1.0),
-                                        game_assets.star_node_h2.clone(),
-                                    ),
-                                    (
-                                        Vec3::new(-96.0, -48.0, 1.0),
-                                        game_assets.star_node_h3.clone(),
-                                    ),
-                                    (
-                                        Vec3::new(-96.0, 48.0, 1.0),
-                                        game_assets.star_node_h4.clone(),
-                                    ),
-                                    (Vec3::new(0.0, 112.0, 1.0), game_assets.star_node_h5.clone()),
-                                    (Vec3::new(96.0, 48.0, 1.0), game_assets.star_node_h6.clone()),
-                                ],
-                            )
-                        };
-
-                        commands
-                            .spawn((
-                                Sprite {
-                                    image: star_config.0,
-                                    ..default()
-                                },
-                                Transform::from_xyz(star.x, star.y, 1.0),
-                                RigidBody::Fixed,
-                                Collider::ball(20.0),
-                                StarCore(marker),
-                                CollisionGroups::new(
-                                    Group::from_bits_truncate(0b00010000),
-                                    Group::from_bits_truncate(0b00100000),
-                                ),
-                                Explodable(ExplodableType::StarCore),
-                                RenderLayers::layer(0),
-                                LevelNode,
-                                GameNode,
-                                Name::from("STAR"),
-                            ))
-                            .with_children(|parent| {
-                                for (pos, atlas) in star_config.1 {
-                                    parent
-                                        .spawn((
-                                            Sprite {
-                                                image: atlas.texture.clone(),
-                                                texture_atlas: Some(TextureAtlas {
-                                                    layout: atlas.layout.clone(),
-                                                    index: 0,
-                                                }),
-                                                ..default()
-                                            },
-                                            Transform {
-                                                translation: pos,
-                                                ..default()
-                                            },
-                                            RigidBody::Fixed,
-                                            Collider::ball(32.0),
-                                            StarNode(Timer::from_seconds(
-                                                thread_rng().gen_range(0.5..3.5),
-                                                TimerMode::Once,
-                                            )),
-                                            Explodable(ExplodableType::StarNode),
-                                            Sensor,
-                                            CollisionGroups::new(
-                                                Group::from_bits_truncate(0b0001000),
-                                                Group::from_bits_truncate(0b0100001),
-                                            ),
-                                            RenderLayers::layer(0),
-                                        ))
-                                        .with_children(|node| {
-                                            node.spawn(Collider::ball(300.0)).insert(Sensor);
-                                        });
-                                }
-                            });
+                        spawn_star(&mut commands, &game_assets, star.x, star.y, star.vert);
                     }
 
                     for rock in &level.rocks {
-                        let texture = if rock.mine {
-                            game_assets.mine.clone()
-                        } else {
-                            game_assets.asteroid.clone()
-                        };
-
-                        commands.spawn((
-                            Sprite {
-                                image: texture,
-                                ..default()
-                            },
-                            Transform {
-                                translation: Vec3::new(rock.x, rock.y, 10.0),
-                                ..default()
-                            },
-                            RigidBody::Fixed,
-                            Collider::ball(28.0),
-                            Sensor,
-                            CollisionGroups::new(
-                                Group::from_bits_truncate(0b0000010),
-                                Group::from_bits_truncate(0b1100101),
-                            ),
-                            Explodable(ExplodableType::Rock),
-                            RenderLayers::layer(0),
-                            LevelNode,
-                            GameNode,
-                        ));
+                        spawn_rock(&mut commands, &game_assets, rock.x, rock.y, rock.mine);
                     }
                 }
 
@@ -673,6 +659,7 @@ fn countdown(
                 for ent in &q_countdown_text {
                     commands.entity(ent).despawn();
                 }
+                commands.write_message(SfxEvent::new(Sfx::Countdown));
 
                 commands.spawn((
                     Sprite {
@@ -691,6 +678,7 @@ fn countdown(
             }
 
             if game.countdown == 0 {
+                commands.write_message(Announce(Callout::LevelStart));
                 game.level_start_seconds = time.elapsed_secs();
                 game_state.set(ClassicGameState::Play);
                 q_player.single_mut().unwrap().linvel = Vec2::new(0.0, 400.0);
@@ -707,10 +695,35 @@ fn countdown(
     }
 }
 
+fn start_red_alert(commands: &mut Commands, game: &mut Game, game_assets: &GameAssets) {
+    game.red_alert = true;
+    commands.write_message(SfxEvent::new(Sfx::RedAlert));
+    commands.insert_resource(MusicCue(Some(Track::RedAlert)));
+    commands.write_message(Announce(Callout::ConditionRed));
+
+    commands.spawn((
+        Sprite {
+            image: game_assets.red_alert.texture.clone(),
+            texture_atlas: Some(TextureAtlas::from(game_assets.red_alert.layout.clone())),
+            ..default()
+        },
+        Transform::from_xyz(0.0, (750.0 / 2.0) - 20.0, 10.0),
+        Animation {
+            timer: Timer::from_seconds(0.5, TimerMode::Repeating),
+            n_sprites: 2,
+            one_time: false,
+        },
+        RenderLayers::layer(2),
+        RedAlert,
+        LevelNode,
+        GameNode,
+    ));
+}
+
 fn spawn_enemy_ships(
     mut commands: Commands,
     time: Res<Time>,
-    levels: Res<Levels>,
+    levels: GameLevels,
     mut game: ResMut<Game>,
     game_assets: Res<GameAssets>,
     q_i_type: Query<Entity, (With<IType>, Without<PType>)>,
@@ -718,7 +731,7 @@ fn spawn_enemy_ships(
     q_cam_offest: Query<&CameraOffset>,
 ) {
     // TODO maybe put current level in a Resource
-    let level = &levels.0[game.level - 1];
+    let level = levels.get(game.level).unwrap();
     let mut rng = rand::rng();
     let mut max_i = level.start_i;
     let mut max_p = level.start_p;
@@ -731,25 +744,7 @@ fn spawn_enemy_ships(
         max_p = level.max_p;
     } else {
         if time.elapsed_secs() - game.level_start_seconds > level.time_limit as f32 {
-            game.red_alert = true;
-
-            commands.spawn((
-                Sprite {
-                    image: game_assets.red_alert.texture.clone(),
-                    texture_atlas: Some(TextureAtlas::from(game_assets.red_alert.layout.clone())),
-                    ..default()
-                },
-                Transform::from_xyz(0.0, (750.0 / 2.0) - 20.0, 10.0),
-                Animation {
-                    timer: Timer::from_seconds(0.5, TimerMode::Repeating),
-                    n_sprites: 2,
-                    one_time: false,
-                },
-                RenderLayers::layer(2),
-                RedAlert,
-                LevelNode,
-                GameNode,
-            ));
+            start_red_alert(&mut commands, &mut game, &game_assets);
         }
     }
 
@@ -850,6 +845,159 @@ fn spawn_enemy_ships(
     }
 }
 
+fn spawn_spy(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut game: ResMut<Game>,
+    game_assets: Res<GameAssets>,
+    q_spy: Query<(), With<Spy>>,
+    q_cam_offest: Query<&CameraOffset>,
+) {
+    // no point spying once Red Alert is on
+    if game.red_alert || !q_spy.is_empty() {
+        return;
+    }
+
+    game.spy_timer.tick(time.delta());
+    if !game.spy_timer.just_finished() {
+        return;
+    }
+
+    if let Ok(offset) = q_cam_offest.single() {
+        let mut rng = rand::rng();
+        let angle: f32 = rng.random_range(-PI..PI);
+        let trans = Vec3::new(angle.cos(), angle.sin(), 10.0)
+            * Vec3::new(650.0 * 1.25, 650.0 * 1.25, 1.0)
+            + offset.0;
+
+        commands.spawn((
+            Sprite {
+                image: game_assets.i_type.clone(),
+                color: Color::srgb(1.0, 0.9, 0.2),
+                ..default()
+            },
+            Transform {
+                translation: trans,
+                rotation: Quat::from_rotation_z(angle + PI),
+                ..default()
+            },
+            Velocity::default(),
+            RigidBody::Dynamic,
+            Collider::ball(26.0),
+            Sensor,
+            EnemyShip {
+                target: None,
+                time_got_target: None,
+                speed: 350.0,
+                turn_radius: 0.06,
+            },
+            CollisionGroups::new(
+                Group::from_bits_truncate(0b0000100),
+                Group::from_bits_truncate(0b1100111),
+            ),
+            Explodable(ExplodableType::EType),
+            Spy {
+                seen_player: false,
+            },
+            RenderLayers::layer(0),
+            LevelNode,
+            GameNode,
+        ));
+    }
+}
+
+// the spy flies at the player like any other ship, then
+// runs for it and calls Red Alert if it isn't shot down
+fn spy_update(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut game: ResMut<Game>,
+    game_assets: Res<GameAssets>,
+    mut q_spy: Query<(Entity, &Transform, &mut EnemyShip, &mut Spy)>,
+    q_player: Query<&CameraOffset>,
+    mut announcements: MessageWriter<Announce>,
+) {
+    let Ok(player_pos) = q_player.single() else {
+        return;
+    };
+    let player_pos = player_pos.0.truncate();
+
+    for (ent, trans, mut ship, mut spy) in q_spy.iter_mut() {
+        let pos = trans.translation.truncate();
+        let distance = pos.distance(player_pos);
+
+        if !spy.seen_player {
+            spy.seen_player = distance < SPY_SIGHT_DISTANCE;
+            if spy.seen_player {
+                announcements.write(Announce(Callout::SpySighted));
+            }
+            continue;
+        }
+
+        if distance > SPY_ESCAPE_DISTANCE {
+            commands.entity(ent).despawn();
+            if !game.red_alert {
+                start_red_alert(&mut commands, &mut game, &game_assets);
+            }
+            continue;
+        }
+
+        // keep the target fresh so move_enemy_ships doesn't point it back at the player
+        let away = (pos - player_pos).normalize_or(Vec2::X);
+        ship.target = Some(player_pos + away * SPY_ESCAPE_DISTANCE * 2.0);
+        ship.time_got_target = Some(time.elapsed_secs());
+    }
+}
+
+fn spawn_formations(
+    mut commands: Commands,
+    time: Res<Time>,
+    levels: GameLevels,
+    mut game: ResMut<Game>,
+    game_assets: Res<GameAssets>,
+    q_cam_offest: Query<&CameraOffset>,
+    mut announcements: MessageWriter<Announce>,
+) {
+    let level = levels.get(game.level).unwrap();
+    let Some(formation) = level.formations.get(game.formations_spawned) else {
+        return;
+    };
+
+    if time.elapsed_secs() - game.level_start_seconds < formation.time {
+        return;
+    }
+
+    if let Ok(offset) = q_cam_offest.single() {
+        // come in from off screen, heading roughly for the player
+        let mut rng = rand::rng();
+        let angle: f32 = rng.random_range(-PI..PI);
+        let pos = Vec2::from_angle(angle) * 650.0 * 1.25 + offset.0.truncate();
+        let heading = angle + PI + rng.random_range(-0.5..0.5);
+
+        spawn_formation(&mut commands, &game_assets, pos, heading, formation.escorts);
+        game.formations_spawned += 1;
+        announcements.write(Announce(Callout::FormationAttack));
+    }
+}
+
+fn formation_bonus(
+    mut commands: Commands,
+    mut game: ResMut<Game>,
+    q_formations: Query<(Entity, &Formation)>,
+    q_members: Query<&FormationMember>,
+) {
+    for (ent, formation) in q_formations.iter() {
+        if q_members.iter().any(|member| member.0 == ent) {
+            continue;
+        }
+
+        if formation.shot == formation.size {
+            game.score += FORMATION_BONUS;
+        }
+        commands.entity(ent).despawn();
+    }
+}
+
 fn listen_update_lives(
     mut commands: Commands,
     mut events: MessageReader<UpdateLivesEvent>,
@@ -884,6 +1032,42 @@ fn listen_update_lives(
     }
 }
 
+fn update_score(game: Res<Game>, mut q_score_text: Query<&mut Text2d, With<ScoreText>>) {
+    for mut text in &mut q_score_text {
+        let score = format!("Score {}", game.score);
+        if text.0 != score {
+            text.0 = score;
+        }
+    }
+}
+
+fn award_extra_lives(
+    mut game: ResMut<Game>,
+    extra_lives: Res<ExtraLives>,
+    mut sfx_events: MessageWriter<SfxEvent>,
+    mut life_events: MessageWriter<UpdateLivesEvent>,
+) {
+    if game.score < game.next_extra_life {
+        return;
+    }
+
+    // a big enough score can pass more than one threshold at once
+    let lives = game.lives;
+    while game.score >= game.next_extra_life {
+        game.next_extra_life += extra_lives.every;
+        if game.lives < extra_lives.max_lives {
+            game.lives += 1;
+        }
+    }
+
+    if game.lives == lives {
+        return;
+    }
+
+    sfx_events.write(SfxEvent::new(Sfx::ExtraLife));
+    life_events.write(UpdateLivesEvent);
+}
+
 fn listen_player_death_classic(
     mut events: MessageReader<PlayerDeathEvent>,
     mut game_state: ResMut<NextState<ClassicGameState>>,
@@ -892,7 +1076,6 @@ fn listen_player_death_classic(
     mut minimap: Query<&mut Camera, With<MinimapCamera>>,
 ) {
     for _ in events.read() {
-        println!("Event in listen_player_death_classic");
         game.lives -= 1;
 
         let mut cam = minimap.single_mut().unwrap();
@@ -916,6 +1099,7 @@ fn star_update(
     mut game_state: ResMut<NextState<ClassicGameState>>,
     mut level_events: MessageWriter<SetupLevel>,
     mut explosion_events: MessageWriter<ExplosionEvent>,
+    mut sfx_events: MessageWriter<SfxEvent>,
     q_stars: Query<(Entity, &StarCore, &GlobalTransform, &Children)>,
     q_star_node: Query<&StarNode>,
     q_level_nodes: Query<Entity, With<LevelNode>>,
@@ -933,7 +1117,12 @@ fn star_update(
         if node_count == 0 {
             commands.entity(star.0).despawn();
             commands.entity(ent).despawn();
+            game.score += STAR_PEELED_POINTS;
 
+            sfx_events.write(SfxEvent::at(
+                Sfx::StarDestroyed,
+                trans.translation().truncate(),
+            ));
             explosion_events.write(ExplosionEvent {
                 size: ExplosionSize::Big,
                 x: trans.translation().x,
@@ -942,6 +1131,10 @@ fn star_update(
         }
     }
     if star_count == 0 {
+        if !game.red_alert {
+            game.score += NO_RED_ALERT_BONUS;
+        }
+
         for ent in &q_level_nodes {
             commands.entity(ent).despawn();
         }
@@ -952,21 +1145,51 @@ fn star_update(
     }
 }
 
+// points, formation progress and power-up drops for everything the player shoots down
+fn score_kills(
+    mut commands: Commands,
+    mut game: ResMut<Game>,
+    game_assets: Res<GameAssets>,
+    mut kill_events: MessageReader<KillEvent>,
+    mut q_formations: Query<&mut Formation>,
+) {
+    for kill in kill_events.read() {
+        game.score += match kill.explodable {
+            ExplodableType::StarCore => STAR_CORE_POINTS,
+            ExplodableType::EType => SPY_BONUS,
+            _ => kill.explodable.points(),
+        };
+
+        if let Some(mut formation) = kill.formation.and_then(|f| q_formations.get_mut(f).ok()) {
+            formation.shot += 1;
+        }
+
+        maybe_drop_power_up(&mut commands, &game_assets, &kill.explodable, kill.pos);
+    }
+}
+
 fn check_collisions(
     mut commands: Commands,
     mut game: ResMut<Game>,
     rapier_context: ReadRapierContext,
-    mut q_player: Query<(Entity, &GlobalTransform), With<Player>>,
+    mut q_player: Query<(Entity, &GlobalTransform, &mut PowerUps), With<Player>>,
     q_explodables: Query<(Entity, &GlobalTransform, &Explodable), With<Explodable>>,
     q_collidables: Query<(Entity, &GlobalTransform, &Collidable), With<Collidable>>,
     mut explosion_events: MessageWriter<ExplosionEvent>,
     mut player_death_events: MessageWriter<PlayerDeathEvent>,
     q_stars: Query<(Entity, &StarCore)>,
+    q_hatches: Query<&Hatch>,
+    q_player_lasers: Query<(), With<PlayerProjectile>>,
+    q_formation_members: Query<&FormationMember>,
+    mut kill_events: MessageWriter<KillEvent>,
     mut q_star_node_textures: Query<&mut Sprite, With<StarNode>>,
 ) {
     // maybe not the best, if player is gone, do we still want explo-explo actions?
-    if let Ok((player, p_trans)) = q_player.single_mut() {
-        let mut p = true;
+    if let Ok((player, p_trans, mut power_ups)) = q_player.single_mut() {
+        // however many things hit the player this frame, it's one hit
+        let mut player_hit = false;
+        // every pair shows up twice below, only score each hit once
+        let mut scored = HashSet::new();
         let context = rapier_context.single().unwrap();
         for (e_ent, e_trans, explo) in q_explodables.iter() {
             // STEP 1 -- Explodable-Explodable interactions
@@ -978,6 +1201,20 @@ fn check_collisions(
 
                 if context.intersection_pair(e_ent, e_ent2) == Some(true) {
                     for (ent, trans, exp) in [(e_ent, e_trans, explo), (e_ent2, e_trans2, explo2)] {
+                        // the core only takes hits while its hatch is open
+                        if q_hatches.get(ent).is_ok_and(|hatch| hatch.state != HatchState::Open) {
+                            continue;
+                        }
+
+                        let other = if ent == e_ent { e_ent2 } else { e_ent };
+                        if q_player_lasers.contains(other) && scored.insert(ent) {
+                            kill_events.write(KillEvent {
+                                explodable: exp.0,
+                                pos: trans.translation().truncate(),
+                                formation: q_formation_members.get(ent).ok().map(|member| member.0),
+                            });
+                        }
+
                         match exp.0 {
                             ExplodableType::StarNode => {
                                 if let Ok(mut sprite) = q_star_node_textures.get_mut(e_ent) {
@@ -1005,20 +1242,13 @@ fn check_collisions(
                                 });
                             }
                             ExplodableType::StarCore => {
-                                for (star_ent, star) in q_stars.iter() {
-                                    if star_ent == ent {
-                                        commands.entity(star.0).despawn();
-                                        break;
-                                    }
-                                }
-
-                                commands.entity(ent).despawn();
-
-                                explosion_events.write(ExplosionEvent {
-                                    size: ExplosionSize::Big,
-                                    x: trans.translation().x,
-                                    y: trans.translation().y,
-                                });
+                                destroy_star(
+                                    &mut commands,
+                                    &q_stars,
+                                    ent,
+                                    trans,
+                                    &mut explosion_events,
+                                );
                             }
                             ExplodableType::Laser => {
                                 commands.entity(ent).despawn();
@@ -1064,19 +1294,24 @@ fn check_collisions(
                             }
                         }
                     }
+                    // same as a laser, it only gets through an open hatch
+                    ExplodableType::StarCore => {
+                        if q_hatches.get(e_ent).is_ok_and(|hatch| hatch.state == HatchState::Open) {
+                            destroy_star(
+                                &mut commands,
+                                &q_stars,
+                                e_ent,
+                                e_trans,
+                                &mut explosion_events,
+                            );
+                        }
+                    }
                     _ => commands.entity(e_ent).despawn(),
                 }
 
-                commands.entity(player).despawn();
-                player_death_events.write(PlayerDeathEvent {});
+                player_hit = true;
 
-                explosion_events.write(ExplosionEvent {
-                    size: ExplosionSize::Small,
-                    x: p_trans.translation().x,
-                    y: p_trans.translation().y,
-                });
-
-                if explo.0 != ExplodableType::Laser {
+                if !matches!(explo.0, ExplodableType::Laser | ExplodableType::StarCore) {
                     explosion_events.write(ExplosionEvent {
                         size: ExplosionSize::Small,
                         x: e_trans.translation().x,
@@ -1085,7 +1320,7 @@ fn check_collisions(
                 }
             }
 
-            // STEP 3 -- Explodable-Collidable & Player-Collidable Interactions
+            // STEP 3 -- Explodable-Collidable Interactions
             for (c_ent, _, __) in q_collidables.iter() {
                 if context.intersection_pair(e_ent, c_ent) == Some(true) {
                     match explo.0 {
@@ -1095,36 +1330,66 @@ fn check_collisions(
                         _ => {}
                     }
                 }
+            }
+        }
 
-                if p && context.intersection_pair(c_ent, player) == Some(true) {
-                    commands.entity(player).despawn();
-                    player_death_events.write(PlayerDeathEvent {});
-                    p = false;
+        // STEP 4 -- Player-Collidable Interactions
+        player_hit |= q_collidables
+            .iter()
+            .any(|(c_ent, _, _)| context.intersection_pair(c_ent, player) == Some(true));
 
-                    explosion_events.write(ExplosionEvent {
-                        size: ExplosionSize::Small,
-                        x: p_trans.translation().x,
-                        y: p_trans.translation().y,
-                    });
-                }
-            }
+        // a shield takes the hit instead
+        if player_hit && !power_ups.absorb_hit() {
+            commands.entity(player).despawn();
+            player_death_events.write(PlayerDeathEvent {});
+
+            explosion_events.write(ExplosionEvent {
+                size: ExplosionSize::Small,
+                x: p_trans.translation().x,
+                y: p_trans.translation().y,
+            });
         }
     }
 }
 
+// the core goes up and takes its star with it
+fn destroy_star(
+    commands: &mut Commands,
+    q_stars: &Query<(Entity, &StarCore)>,
+    core: Entity,
+    trans: &GlobalTransform,
+    explosion_events: &mut MessageWriter<ExplosionEvent>,
+) {
+    if let Ok((_, star)) = q_stars.get(core) {
+        commands.entity(star.0).despawn();
+    }
+
+    commands.entity(core).despawn();
+
+    explosion_events.write(ExplosionEvent {
+        size: ExplosionSize::Big,
+        x: trans.translation().x,
+        y: trans.translation().y,
+    });
+}
+
 /*
     * member
     . filter
     & member and filter
 
-             8 7 6 5 4 3 2 1
-    Player   . .     . . . *
-     Rocks     . .     . * .
-   Fighter     . .     & . .
-  StarNode       .   *     .
-  StarCore       . *
-   P-Laser   .   * . . . .
-   S-Laser     *       . . .
-Collidable   *   .         .
+             10 9 8 7 6 5 4 3 2 1
+    Player    . . . .     . . . *
+     Rocks          . .     . * .
+   Fighter          . .     & . .
+ Formation          . .     * . .
+       Spy          . .     & . .
+  StarNode            .   *     .
+  StarCore            . *
+   P-Laser      . .   * . . . .
+   S-Laser          *       . . .
+   Missile      *     .         .
+Collidable        *   .         .
+    Pickup    *                 .
 
 */
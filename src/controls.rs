@@ -0,0 +1,561 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::{storage, AppState, GameAssets};
+
+const STORAGE_KEY: &str = "controls";
+const IDLE_COLOR: Color = Color::srgb(0.15, 0.15, 0.15);
+const HOVER_COLOR: Color = Color::srgb(0.25, 0.25, 0.25);
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Up,
+    Down,
+    Left,
+    Right,
+    Fire,
+    Pause,
+}
+
+impl Action {
+    const ALL: [Action; 6] = [
+        Action::Up,
+        Action::Down,
+        Action::Left,
+        Action::Right,
+        Action::Fire,
+        Action::Pause,
+    ];
+
+    fn label(&self) -> &'static str {
+        match self {
+            Action::Up => "Up",
+            Action::Down => "Down",
+            Action::Left => "Left",
+            Action::Right => "Right",
+            Action::Fire => "Fire",
+            Action::Pause => "Pause",
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Device {
+    Keyboard,
+    Gamepad,
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize)]
+pub struct Binding {
+    pub key: KeyCode,
+    pub button: GamepadButton,
+}
+
+// what each action is bound to, saved between runs. the left stick
+// always steers as well, whatever the d-pad is bound to
+#[derive(Resource, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Controls {
+    up: Binding,
+    down: Binding,
+    left: Binding,
+    right: Binding,
+    fire: Binding,
+    pause: Binding,
+}
+
+impl Default for Controls {
+    fn default() -> Self {
+        Controls {
+            up: Binding {
+                key: KeyCode::KeyW,
+                button: GamepadButton::DPadUp,
+            },
+            down: Binding {
+                key: KeyCode::KeyS,
+                button: GamepadButton::DPadDown,
+            },
+            left: Binding {
+                key: KeyCode::KeyA,
+                button: GamepadButton::DPadLeft,
+            },
+            right: Binding {
+                key: KeyCode::KeyD,
+                button: GamepadButton::DPadRight,
+            },
+            fire: Binding {
+                key: KeyCode::Enter,
+                button: GamepadButton::South,
+            },
+            pause: Binding {
+                key: KeyCode::Escape,
+                button: GamepadButton::Start,
+            },
+        }
+    }
+}
+
+impl Controls {
+    pub fn get(&self, action: Action) -> Binding {
+        match action {
+            Action::Up => self.up,
+            Action::Down => self.down,
+            Action::Left => self.left,
+            Action::Right => self.right,
+            Action::Fire => self.fire,
+            Action::Pause => self.pause,
+        }
+    }
+
+    fn get_mut(&mut self, action: Action) -> &mut Binding {
+        match action {
+            Action::Up => &mut self.up,
+            Action::Down => &mut self.down,
+            Action::Left => &mut self.left,
+            Action::Right => &mut self.right,
+            Action::Fire => &mut self.fire,
+            Action::Pause => &mut self.pause,
+        }
+    }
+
+    // on the keyboard or any connected pad
+    pub fn pressed<'a>(
+        &self,
+        action: Action,
+        kb: &ButtonInput<KeyCode>,
+        gamepads: impl IntoIterator<Item = &'a Gamepad>,
+    ) -> bool {
+        let binding = self.get(action);
+        kb.pressed(binding.key) || gamepads.into_iter().any(|pad| pad.pressed(binding.button))
+    }
+
+    pub fn just_pressed<'a>(
+        &self,
+        action: Action,
+        kb: &ButtonInput<KeyCode>,
+        gamepads: impl IntoIterator<Item = &'a Gamepad>,
+    ) -> bool {
+        let binding = self.get(action);
+        kb.just_pressed(binding.key)
+            || gamepads
+                .into_iter()
+                .any(|pad| pad.just_pressed(binding.button))
+    }
+
+    // binds `action`, whatever already had that key or button gets the
+    // old one instead so nothing ends up doubled or unbound. returns the
+    // action that was swapped, if any
+    fn rebind(&mut self, action: Action, device: Device, to: Binding) -> Option<Action> {
+        let old = self.get(action);
+        let clash = Action::ALL.into_iter().find(|other| {
+            *other != action
+                && match device {
+                    Device::Keyboard => self.get(*other).key == to.key,
+                    Device::Gamepad => self.get(*other).button == to.button,
+                }
+        });
+
+        if let Some(other) = clash {
+            match device {
+                Device::Keyboard => self.get_mut(other).key = old.key,
+                Device::Gamepad => self.get_mut(other).button = old.button,
+            }
+        }
+        match device {
+            Device::Keyboard => self.get_mut(action).key = to.key,
+            Device::Gamepad => self.get_mut(action).button = to.button,
+        }
+
+        clash
+    }
+}
+
+// waiting on a key or button for this action
+#[derive(Resource, Default)]
+struct Listening(Option<(Action, Device)>);
+
+#[derive(Component)]
+struct ControlsNode;
+
+#[derive(Component)]
+struct ControlsButton(ControlsButtonAction);
+
+#[derive(Clone, Copy)]
+enum ControlsButtonAction {
+    Bind(Action, Device),
+    Defaults,
+    Back,
+}
+
+#[derive(Component)]
+struct BindingText(Action, Device);
+
+#[derive(Component)]
+struct NoticeText;
+
+pub struct ControlsPlugin;
+impl Plugin for ControlsPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(storage::load::<Controls>(STORAGE_KEY).unwrap_or_default())
+            .init_resource::<Listening>()
+            .add_systems(OnEnter(AppState::Controls), setup_controls)
+            .add_systems(
+                Update,
+                (listen, button_system, update_binding_text)
+                    .chain()
+                    .run_if(in_state(AppState::Controls)),
+            )
+            .add_systems(OnExit(AppState::Controls), despawn_controls);
+    }
+}
+
+fn setup_controls(mut commands: Commands, game_assets: Res<GameAssets>) {
+    commands.spawn((Camera2d, ControlsNode, Name::from("Controls Camera")));
+
+    let font = |size: f32| TextFont {
+        font: game_assets.font.clone(),
+        font_size: size,
+        ..default()
+    };
+
+    commands
+        .spawn((
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                padding: UiRect::all(Val::Px(30.0)),
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::SpaceBetween,
+                flex_direction: FlexDirection::Column,
+                ..default()
+            },
+            ControlsNode,
+            Name::from("Controls"),
+        ))
+        .with_children(|parent| {
+            parent.spawn((Text::new("Controls"), TextColor(Color::WHITE), font(40.0)));
+
+            parent
+                .spawn(Node {
+                    flex_direction: FlexDirection::Column,
+                    row_gap: Val::Px(10.0),
+                    ..default()
+                })
+                .with_children(|parent| {
+                    parent
+                        .spawn(Node {
+                            column_gap: Val::Px(20.0),
+                            ..default()
+                        })
+                        .with_children(|parent| {
+                            for title in ["", "Keyboard", "Gamepad"] {
+                                parent
+                                    .spawn(Node {
+                                        width: Val::Px(200.0),
+                                        justify_content: JustifyContent::Center,
+                                        ..default()
+                                    })
+                                    .with_children(|parent| {
+                                        parent.spawn((
+                                            Text::new(title),
+                                            TextColor(Color::srgb(0.58, 0.60, 0.69)),
+                                            font(20.0),
+                                        ));
+                                    });
+                            }
+                        });
+
+                    for action in Action::ALL {
+                        parent
+                            .spawn(Node {
+                                column_gap: Val::Px(20.0),
+                                align_items: AlignItems::Center,
+                                ..default()
+                            })
+                            .with_children(|parent| {
+                                parent
+                                    .spawn(Node {
+                                        width: Val::Px(200.0),
+                                        ..default()
+                                    })
+                                    .with_children(|parent| {
+                                        parent.spawn((
+                                            Text::new(action.label()),
+                                            TextColor(Color::WHITE),
+                                            font(20.0),
+                                        ));
+                                    });
+
+                                for device in [Device::Keyboard, Device::Gamepad] {
+                                    spawn_button(
+                                        parent,
+                                        &game_assets,
+                                        ControlsButtonAction::Bind(action, device),
+                                        BindingText(action, device),
+                                    );
+                                }
+                            });
+                    }
+                });
+
+            parent.spawn((
+                Text::new("Click a binding, then press the new key or button"),
+                TextColor(Color::srgb(1.0, 0.85, 0.0)),
+                font(16.0),
+                NoticeText,
+            ));
+
+            parent
+                .spawn(Node {
+                    column_gap: Val::Px(30.0),
+                    ..default()
+                })
+                .with_children(|parent| {
+                    spawn_button(parent, &game_assets, ControlsButtonAction::Defaults, ());
+                    spawn_button(parent, &game_assets, ControlsButtonAction::Back, ());
+                });
+        });
+}
+
+fn spawn_button(
+    parent: &mut ChildSpawnerCommands,
+    game_assets: &GameAssets,
+    action: ControlsButtonAction,
+    text: impl Bundle,
+) {
+    let label = match action {
+        ControlsButtonAction::Bind(..) => "",
+        ControlsButtonAction::Defaults => "Defaults",
+        ControlsButtonAction::Back => "Back",
+    };
+
+    parent
+        .spawn((
+            Button,
+            Node {
+                width: Val::Px(200.0),
+                height: Val::Px(44.0),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            BorderRadius::all(Val::Px(10.0)),
+            BackgroundColor(IDLE_COLOR),
+            ControlsButton(action),
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new(label),
+                TextColor(Color::srgb(0.9, 0.9, 0.9)),
+                TextFont {
+                    font: game_assets.font.clone(),
+                    font_size: 20.0,
+                    ..default()
+                },
+                text,
+            ));
+        });
+}
+
+fn key_name(key: KeyCode) -> String {
+    let name = format!("{:?}", key);
+    name.strip_prefix("Key")
+        .or(name.strip_prefix("Digit"))
+        .unwrap_or(&name)
+        .to_string()
+}
+
+// the next key or button pressed while listening becomes the binding,
+// escape gives up
+fn listen(
+    kb: Res<ButtonInput<KeyCode>>,
+    q_gamepads: Query<&Gamepad>,
+    mut listening: ResMut<Listening>,
+    mut controls: ResMut<Controls>,
+    mut q_notice: Query<&mut Text, With<NoticeText>>,
+) {
+    let Some((action, device)) = listening.0 else {
+        return;
+    };
+
+    if kb.just_pressed(KeyCode::Escape) {
+        listening.0 = None;
+        for mut text in &mut q_notice {
+            text.0 = format!("{} left as it was", action.label());
+        }
+        return;
+    }
+
+    let mut to = controls.get(action);
+    match device {
+        Device::Keyboard => match kb.get_just_pressed().next() {
+            Some(key) => to.key = *key,
+            None => return,
+        },
+        Device::Gamepad => {
+            match q_gamepads
+                .iter()
+                .find_map(|pad| pad.get_just_pressed().next().copied())
+            {
+                Some(button) => to.button = button,
+                None => return,
+            }
+        }
+    }
+
+    listening.0 = None;
+    let notice = match controls.rebind(action, device, to) {
+        Some(other) => format!("{} was on that too, it's been swapped", other.label()),
+        None => format!("{} rebound", action.label()),
+    };
+    for mut text in &mut q_notice {
+        text.0 = notice.clone();
+    }
+    storage::save(STORAGE_KEY, &*controls);
+}
+
+fn button_system(
+    mut interaction_query: Query<
+        (&Interaction, &ControlsButton, &mut BackgroundColor),
+        Changed<Interaction>,
+    >,
+    mut listening: ResMut<Listening>,
+    mut controls: ResMut<Controls>,
+    mut app_state: ResMut<NextState<AppState>>,
+    mut q_notice: Query<&mut Text, With<NoticeText>>,
+) {
+    for (interaction, button, mut color) in &mut interaction_query {
+        *color = match *interaction {
+            Interaction::Pressed | Interaction::Hovered => HOVER_COLOR.into(),
+            Interaction::None => IDLE_COLOR.into(),
+        };
+
+        // the press that picked a binding can't also pick the next one
+        if *interaction != Interaction::Pressed || listening.is_changed() {
+            continue;
+        }
+
+        match button.0 {
+            ControlsButtonAction::Bind(action, device) => {
+                listening.0 = Some((action, device));
+                for mut text in &mut q_notice {
+                    text.0 = match device {
+                        Device::Keyboard => {
+                            format!("Press a key for {}, Esc to cancel", action.label())
+                        }
+                        Device::Gamepad => {
+                            format!("Press a button for {}, Esc to cancel", action.label())
+                        }
+                    };
+                }
+            }
+            ControlsButtonAction::Defaults => {
+                listening.0 = None;
+                *controls = Controls::default();
+                storage::save(STORAGE_KEY, &*controls);
+                for mut text in &mut q_notice {
+                    text.0 = "Back to the defaults".to_string();
+                }
+            }
+            ControlsButtonAction::Back => app_state.set(AppState::Settings),
+        }
+    }
+}
+
+fn update_binding_text(
+    controls: Res<Controls>,
+    listening: Res<Listening>,
+    mut q_text: Query<(&mut Text, &BindingText)>,
+    q_new: Query<(), Added<BindingText>>,
+) {
+    if !controls.is_changed() && !listening.is_changed() && q_new.is_empty() {
+        return;
+    }
+
+    for (mut text, BindingText(action, device)) in &mut q_text {
+        text.0 = if listening.0 == Some((*action, *device)) {
+            "...".to_string()
+        } else {
+            let binding = controls.get(*action);
+            match device {
+                Device::Keyboard => key_name(binding.key),
+                Device::Gamepad => format!("{:?}", binding.button),
+            }
+        };
+    }
+}
+
+fn despawn_controls(
+    mut commands: Commands,
+    mut listening: ResMut<Listening>,
+    query: Query<Entity, With<ControlsNode>>,
+) {
+    listening.0 = None;
+    for ent in &query {
+        commands.entity(ent).despawn();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(key: KeyCode) -> Binding {
+        Binding {
+            key,
+            button: GamepadButton::Other(0),
+        }
+    }
+
+    fn button(button: GamepadButton) -> Binding {
+        Binding {
+            key: KeyCode::F24,
+            button,
+        }
+    }
+
+    #[test]
+    fn rebind_to_a_free_key() {
+        let mut controls = Controls::default();
+        assert!(controls
+            .rebind(Action::Fire, Device::Keyboard, key(KeyCode::Space))
+            .is_none());
+        assert_eq!(controls.get(Action::Fire).key, KeyCode::Space);
+        assert_eq!(controls.get(Action::Fire).button, GamepadButton::South);
+    }
+
+    #[test]
+    fn rebind_swaps_a_key_in_use() {
+        let mut controls = Controls::default();
+        let swapped = controls.rebind(Action::Up, Device::Keyboard, key(KeyCode::KeyS));
+        assert!(swapped == Some(Action::Down));
+        assert_eq!(controls.get(Action::Up).key, KeyCode::KeyS);
+        assert_eq!(controls.get(Action::Down).key, KeyCode::KeyW);
+    }
+
+    #[test]
+    fn rebind_swaps_a_button_in_use() {
+        let mut controls = Controls::default();
+        let swapped = controls.rebind(Action::Pause, Device::Gamepad, button(GamepadButton::South));
+        assert!(swapped == Some(Action::Fire));
+        assert_eq!(controls.get(Action::Pause).button, GamepadButton::South);
+        assert_eq!(controls.get(Action::Fire).button, GamepadButton::Start);
+        // the keys stay put
+        assert_eq!(controls.get(Action::Pause).key, KeyCode::Escape);
+        assert_eq!(controls.get(Action::Fire).key, KeyCode::Enter);
+    }
+
+    #[test]
+    fn rebind_to_the_same_key_changes_nothing() {
+        let mut controls = Controls::default();
+        assert!(controls
+            .rebind(Action::Left, Device::Keyboard, key(KeyCode::KeyA))
+            .is_none());
+        for action in Action::ALL {
+            assert_eq!(
+                controls.get(action).key,
+                Controls::default().get(action).key
+            );
+        }
+    }
+}
@@ -0,0 +1,277 @@
+use bevy::{
+    asset::{io::Reader, AssetLoader, LoadContext},
+    prelude::*,
+};
+use serde::Deserialize;
+use std::collections::HashSet;
+
+use super::game::{EnemyShip, Player, PlayerProjectile, Projectile};
+
+// enemies and enemy shots passing this close to the player count as near misses
+const NEAR_MISS_DISTANCE: f32 = 80.0;
+
+// (seconds, value) points, straight lines in between and flat past either end
+#[derive(Clone, Deserialize)]
+#[serde(transparent)]
+pub struct Curve(Vec<(f32, f32)>);
+
+impl Curve {
+    fn at(&self, x: f32) -> f32 {
+        let Some(&(x0, y0)) = self.0.first() else {
+            return 0.0;
+        };
+        if x <= x0 {
+            return y0;
+        }
+
+        for pair in self.0.windows(2) {
+            let ((xa, ya), (xb, yb)) = (pair[0], pair[1]);
+            if x <= xb {
+                return ya + (yb - ya) * (x - xa) / (xb - xa);
+            }
+        }
+
+        self.0[self.0.len() - 1].1
+    }
+
+    // anything `at` can't draw a line through
+    fn check(&self, name: &str) -> Result<(), BevyError> {
+        if self.0.is_empty() {
+            return Err(format!("{name} has no points").into());
+        }
+        if self.0.iter().any(|(x, y)| !x.is_finite() || !y.is_finite()) {
+            return Err(format!("{name} has a point that isn't a number").into());
+        }
+        if self.0.windows(2).any(|pair| pair[0].0 >= pair[1].0) {
+            return Err(format!("{name} has seconds that don't go up").into());
+        }
+        Ok(())
+    }
+}
+
+// how the player's doing moves the director along the curves,
+// ahead when they're cruising and back when they're struggling
+#[derive(Clone, Deserialize)]
+pub struct Performance {
+    // seconds of play kills and near misses are counted over
+    pub window: f32,
+    // kills per window the curves are tuned for
+    pub par_kills: f32,
+    pub seconds_per_kill: f32,
+    pub seconds_per_near_miss: f32,
+    pub seconds_per_death: f32,
+    // how long a death still counts, across runs
+    pub death_memory: f32,
+    // the most performance can move things either way
+    pub max_adjustment: f32,
+}
+
+// Endless difficulty curves, see assets/endless.difficulty.ron
+#[derive(Asset, TypePath, Clone, Deserialize)]
+pub struct Difficulty {
+    pub max_fighters: Curve,
+    pub max_stars: Curve,
+    // these three multiply the usual values
+    pub fighter_speed: Curve,
+    pub fighter_turn: Curve,
+    pub node_fire_interval: Curve,
+    pub performance: Performance,
+}
+
+impl Difficulty {
+    fn from_ron(bytes: &[u8]) -> Result<Difficulty, BevyError> {
+        let difficulty: Difficulty = ron::de::from_bytes(bytes)?;
+
+        for (name, curve) in [
+            ("max_fighters", &difficulty.max_fighters),
+            ("max_stars", &difficulty.max_stars),
+            ("fighter_speed", &difficulty.fighter_speed),
+            ("fighter_turn", &difficulty.fighter_turn),
+            ("node_fire_interval", &difficulty.node_fire_interval),
+        ] {
+            curve.check(name)?;
+        }
+        Ok(difficulty)
+    }
+}
+
+#[derive(Default)]
+pub struct DifficultyLoader;
+
+impl AssetLoader for DifficultyLoader {
+    type Asset = Difficulty;
+    type Settings = ();
+    type Error = BevyError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Difficulty, BevyError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        Difficulty::from_ron(&bytes)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["difficulty.ron"]
+    }
+}
+
+// only around during an Endless run
+#[derive(Resource)]
+pub struct Director {
+    pub max_fighters: usize,
+    pub max_stars: usize,
+    pub fighter_speed: f32,
+    pub fighter_turn: f32,
+    pub node_fire_interval: f32,
+    // when things happened, in elapsed seconds
+    kills: Vec<f32>,
+    near_misses: Vec<f32>,
+    // threats inside NEAR_MISS_DISTANCE last frame
+    close: HashSet<Entity>,
+}
+
+impl Default for Director {
+    fn default() -> Self {
+        Director {
+            max_fighters: 5,
+            max_stars: 3,
+            fighter_speed: 1.0,
+            fighter_turn: 1.0,
+            node_fire_interval: 1.0,
+            kills: Vec::new(),
+            near_misses: Vec::new(),
+            close: HashSet::new(),
+        }
+    }
+}
+
+impl Director {
+    pub fn record_kill(&mut self, now: f32) {
+        self.kills.push(now);
+    }
+
+    // `seconds` into the run, `now` in elapsed seconds
+    pub fn update(&mut self, difficulty: &Difficulty, deaths: &RecentDeaths, seconds: f32, now: f32) {
+        let perf = &difficulty.performance;
+        self.kills.retain(|t| now - t < perf.window);
+        self.near_misses.retain(|t| now - t < perf.window);
+        let deaths = deaths.0.iter().filter(|t| now - **t < perf.death_memory).count();
+
+        // the window isn't full yet early on
+        let par = perf.par_kills * (seconds / perf.window).min(1.0);
+        let adjustment = (self.kills.len() as f32 - par) * perf.seconds_per_kill
+            - self.near_misses.len() as f32 * perf.seconds_per_near_miss
+            - deaths as f32 * perf.seconds_per_death;
+        let x = (seconds + adjustment.clamp(-perf.max_adjustment, perf.max_adjustment)).max(0.0);
+
+        self.max_fighters = difficulty.max_fighters.at(x).round() as usize;
+        self.max_stars = difficulty.max_stars.at(x).round() as usize;
+        self.fighter_speed = difficulty.fighter_speed.at(x);
+        self.fighter_turn = difficulty.fighter_turn.at(x);
+        self.node_fire_interval = difficulty.node_fire_interval.at(x);
+    }
+}
+
+// when Endless runs ended, in elapsed seconds
+#[derive(Resource, Default)]
+pub struct RecentDeaths(pub Vec<f32>);
+
+// counts a near miss when something gets close and then leaves again,
+// anything that hits the player is gone before it can leave
+pub fn spot_near_misses(
+    time: Res<Time>,
+    mut director: ResMut<Director>,
+    q_player: Query<&GlobalTransform, With<Player>>,
+    q_ships: Query<(Entity, &GlobalTransform), With<EnemyShip>>,
+    q_shots: Query<(Entity, &GlobalTransform), With<Projectile>>,
+    q_player_lasers: Query<(), With<PlayerProjectile>>,
+) {
+    let Ok(p_trans) = q_player.single() else {
+        return;
+    };
+
+    let mut close = HashSet::new();
+    let mut left = 0;
+    for (ent, trans) in q_ships.iter().chain(q_shots.iter()) {
+        if q_player_lasers.contains(ent) {
+            continue;
+        }
+
+        let distance = trans
+            .translation()
+            .truncate()
+            .distance(p_trans.translation().truncate());
+        if distance < NEAR_MISS_DISTANCE {
+            close.insert(ent);
+        } else if director.close.contains(&ent) {
+            left += 1;
+        }
+    }
+
+    for _ in 0..left {
+        director.near_misses.push(time.elapsed_secs());
+    }
+    director.close = close;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn curve(points: &[(f32, f32)]) -> Curve {
+        Curve(points.to_vec())
+    }
+
+    #[test]
+    fn at_draws_straight_lines_between_points() {
+        let c = curve(&[(0.0, 5.0), (10.0, 15.0), (30.0, 5.0)]);
+        assert_eq!(c.at(0.0), 5.0);
+        assert_eq!(c.at(5.0), 10.0);
+        assert_eq!(c.at(10.0), 15.0);
+        assert_eq!(c.at(20.0), 10.0);
+    }
+
+    #[test]
+    fn at_is_flat_past_either_end() {
+        let c = curve(&[(10.0, 2.0), (20.0, 4.0)]);
+        assert_eq!(c.at(-5.0), 2.0);
+        assert_eq!(c.at(0.0), 2.0);
+        assert_eq!(c.at(25.0), 4.0);
+        assert_eq!(c.at(1000.0), 4.0);
+    }
+
+    #[test]
+    fn check_rejects_curves_at_cant_use() {
+        assert!(curve(&[]).check("empty").is_err());
+        assert!(curve(&[(0.0, 1.0), (0.0, 2.0)]).check("repeated").is_err());
+        assert!(curve(&[(10.0, 1.0), (5.0, 2.0)])
+            .check("backwards")
+            .is_err());
+        assert!(curve(&[(0.0, f32::NAN)]).check("nan").is_err());
+        assert!(curve(&[(0.0, 1.0), (f32::INFINITY, 2.0)])
+            .check("infinite")
+            .is_err());
+
+        assert!(curve(&[(0.0, 1.0)]).check("one point").is_ok());
+        assert!(curve(&[(0.0, 1.0), (10.0, 1.0)]).check("flat").is_ok());
+    }
+
+    #[test]
+    fn shipped_difficulty_loads() {
+        let bytes = include_bytes!("../assets/endless.difficulty.ron");
+        assert!(Difficulty::from_ron(bytes).is_ok());
+    }
+
+    #[test]
+    fn loading_rejects_a_bad_curve() {
+        let text = include_str!("../assets/endless.difficulty.ron").replace(
+            "fighter_speed: [(0.0, 1.0), (120.0, 1.15)",
+            "fighter_speed: [(0.0, 1.0), (0.0, 1.15)",
+        );
+        assert!(Difficulty::from_ron(text.as_bytes()).is_err());
+    }
+}
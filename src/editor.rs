@@ -0,0 +1,722 @@
+use bevy::{
+    input::mouse::{AccumulatedMouseScroll, MouseScrollUnit},
+    prelude::*,
+    window::PrimaryWindow,
+};
+use bevy_prototype_lyon::prelude::*;
+
+use super::{
+    levels::{self, Level, Levels, Playtest, Rock, Star},
+    AppState, GameAssets,
+};
+
+// level maps use one pixel per 50x50 tile, so snap to the same grid
+const GRID_SIZE: f32 = 50.0;
+// keep everything inside the 5000x5000 game boundary
+const ARENA_EDGE: f32 = 2450.0;
+const PAN_SPEED: f32 = 800.0;
+
+const IDLE_COLOR: Color = Color::srgb(0.15, 0.15, 0.15);
+const HOVER_COLOR: Color = Color::srgb(0.25, 0.25, 0.25);
+const SELECTED_COLOR: Color = Color::srgb(0.2, 0.35, 0.6);
+
+#[derive(Clone, Copy, PartialEq)]
+enum Tool {
+    VertStar,
+    HorizStar,
+    Asteroid,
+    Mine,
+    Move,
+    Erase,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Field {
+    StartI,
+    MaxI,
+    StartP,
+    MaxP,
+    TimeLimit,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum EditorButtonAction {
+    Tool(Tool),
+    Adjust(Field, isize),
+    PrevLevel,
+    NextLevel,
+    New,
+    Save,
+    Playtest,
+    Menu,
+}
+
+// index into the level's stars or rocks
+#[derive(Clone, Copy)]
+enum Item {
+    Star(usize),
+    Rock(usize),
+}
+
+#[derive(Component)]
+struct EditorNode;
+
+#[derive(Component)]
+struct EditorCamera;
+
+#[derive(Component)]
+struct EditorItem;
+
+#[derive(Component)]
+struct EditorButton {
+    action: EditorButtonAction,
+}
+
+#[derive(Component)]
+enum EditorLabel {
+    Field(Field),
+    LevelNumber,
+    Status,
+}
+
+// kept around while playtesting so the level is still here when we come back
+#[derive(Resource)]
+struct Editor {
+    level: Level,
+    number: usize,
+    tool: Tool,
+    dragging: Option<Item>,
+    status: String,
+}
+
+pub struct EditorPlugin;
+impl Plugin for EditorPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(AppState::Editor), setup_editor)
+            .add_systems(
+                Update,
+                (
+                    move_camera,
+                    button_system,
+                    edit_level,
+                    sync_items,
+                    update_labels,
+                    draw_grid,
+                )
+                    .chain()
+                    .run_if(in_state(AppState::Editor)),
+            )
+            .add_systems(OnExit(AppState::Editor), despawn_editor);
+    }
+}
+
+fn setup_editor(
+    mut commands: Commands,
+    game_assets: Res<GameAssets>,
+    levels: Res<Levels>,
+    level_assets: Res<Assets<Level>>,
+    editor: Option<ResMut<Editor>>,
+) {
+    commands.remove_resource::<Playtest>();
+
+    match editor {
+        // items were despawned on the way out, draw them again
+        Some(mut editor) => editor.set_changed(),
+        None => commands.insert_resource(Editor {
+            level: load_level(&levels, &level_assets, 1),
+            number: 1,
+            tool: Tool::Asteroid,
+            dragging: None,
+            status: String::new(),
+        }),
+    }
+
+    commands.spawn((
+        Camera2d,
+        Transform::from_xyz(0.0, 0.0, 999.0),
+        Projection::Orthographic(OrthographicProjection {
+            scale: 2.0,
+            ..OrthographicProjection::default_2d()
+        }),
+        EditorCamera,
+        EditorNode,
+        Name::from("Editor Camera"),
+    ));
+
+    // background tiles
+    for x in (-3000..=3000).step_by(1000) {
+        for y in (-3000..=3000).step_by(1000) {
+            commands.spawn((
+                Sprite {
+                    image: game_assets.background.clone(),
+                    ..default()
+                },
+                Transform::from_xyz(x as f32, y as f32, 0.0),
+                EditorNode,
+            ));
+        }
+    }
+
+    // game boundary
+    commands.spawn((
+        ShapeBuilder::with(&shapes::Rectangle {
+            extents: Vec2::new(5000.0, 5000.0),
+            origin: RectangleOrigin::Center,
+            ..default()
+        })
+        .fill(Color::srgba(0f32, 0f32, 0f32, 0f32))
+        .stroke((Color::srgb(1f32, 0f32, 0f32), 10.0))
+        .build(),
+        EditorNode,
+    ));
+
+    let font = game_assets.font.clone();
+
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(5.0),
+                left: Val::Px(5.0),
+                flex_direction: FlexDirection::Column,
+                ..default()
+            },
+            Interaction::default(),
+            EditorNode,
+            Name::from("Editor Toolbar"),
+        ))
+        .with_children(|parent| {
+            parent.spawn(Node::default()).with_children(|row| {
+                spawn_button(
+                    row,
+                    &font,
+                    "V Star",
+                    EditorButtonAction::Tool(Tool::VertStar),
+                );
+                spawn_button(
+                    row,
+                    &font,
+                    "H Star",
+                    EditorButtonAction::Tool(Tool::HorizStar),
+                );
+                spawn_button(
+                    row,
+                    &font,
+                    "Asteroid",
+                    EditorButtonAction::Tool(Tool::Asteroid),
+                );
+                spawn_button(row, &font, "Mine", EditorButtonAction::Tool(Tool::Mine));
+                spawn_button(row, &font, "Move", EditorButtonAction::Tool(Tool::Move));
+                spawn_button(row, &font, "Erase", EditorButtonAction::Tool(Tool::Erase));
+            });
+
+            parent
+                .spawn(Node {
+                    align_items: AlignItems::Center,
+                    ..default()
+                })
+                .with_children(|row| {
+                    for (field, step) in [
+                        (Field::StartI, 1),
+                        (Field::MaxI, 1),
+                        (Field::StartP, 1),
+                        (Field::MaxP, 1),
+                        (Field::TimeLimit, 5),
+                    ] {
+                        spawn_label(row, &font, EditorLabel::Field(field));
+                        spawn_button(row, &font, "-", EditorButtonAction::Adjust(field, -step));
+                        spawn_button(row, &font, "+", EditorButtonAction::Adjust(field, step));
+                    }
+                });
+
+            parent
+                .spawn(Node {
+                    align_items: AlignItems::Center,
+                    ..default()
+                })
+                .with_children(|row| {
+                    spawn_button(row, &font, "<", EditorButtonAction::PrevLevel);
+                    spawn_label(row, &font, EditorLabel::LevelNumber);
+                    spawn_button(row, &font, ">", EditorButtonAction::NextLevel);
+                    spawn_button(row, &font, "New", EditorButtonAction::New);
+
+                    // no file system on WASM
+                    if !cfg!(all(target_arch = "wasm32", target_os = "unknown")) {
+                        spawn_button(row, &font, "Save", EditorButtonAction::Save);
+                    }
+
+                    spawn_button(row, &font, "Playtest", EditorButtonAction::Playtest);
+                    spawn_button(row, &font, "Menu", EditorButtonAction::Menu);
+                });
+        });
+
+    commands.spawn((
+        Text::new(""),
+        TextColor(Color::WHITE),
+        TextFont {
+            font: font.clone(),
+            font_size: 12.0,
+            ..default()
+        },
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(8.0),
+            left: Val::Px(10.0),
+            ..default()
+        },
+        EditorLabel::Status,
+        EditorNode,
+    ));
+}
+
+fn spawn_button(
+    parent: &mut ChildSpawnerCommands,
+    font: &Handle<Font>,
+    label: &str,
+    action: EditorButtonAction,
+) {
+    parent
+        .spawn((
+            Button,
+            Node {
+                padding: UiRect::all(Val::Px(8.0)),
+                margin: UiRect::all(Val::Px(3.0)),
+                ..default()
+            },
+            BorderRadius::all(Val::Px(6.0)),
+            BackgroundColor(IDLE_COLOR),
+            EditorButton { action },
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new(label),
+                TextColor(Color::srgb(0.9, 0.9, 0.9)),
+                TextFont {
+                    font: font.clone(),
+                    font_size: 12.0,
+                    ..default()
+                },
+            ));
+        });
+}
+
+fn spawn_label(parent: &mut ChildSpawnerCommands, font: &Handle<Font>, label: EditorLabel) {
+    parent.spawn((
+        Text::new(""),
+        TextColor(Color::WHITE),
+        TextFont {
+            font: font.clone(),
+            font_size: 12.0,
+            ..default()
+        },
+        Node {
+            margin: UiRect::horizontal(Val::Px(6.0)),
+            ..default()
+        },
+        label,
+    ));
+}
+
+fn load_level(levels: &Levels, level_assets: &Assets<Level>, number: usize) -> Level {
+    levels
+        .0
+        .get(number - 1)
+        .and_then(|handle| level_assets.get(handle))
+        .cloned()
+        .unwrap_or_default()
+}
+
+// same test as the Save button, the browser is the only place without a file system
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+fn save_level(number: usize, level: &Level) -> Result<String, BevyError> {
+    let path = bevy::asset::io::file::FileAssetReader::get_base_path()
+        .join("assets")
+        .join(levels::level_path(number));
+    std::fs::write(&path, levels::level_to_ron(level)?)?;
+    Ok(path.display().to_string())
+}
+
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+fn save_level(_number: usize, _level: &Level) -> Result<String, BevyError> {
+    Err("levels can't be saved in the browser".into())
+}
+
+fn button_system(
+    mut commands: Commands,
+    mut interaction_query: Query<(&Interaction, &EditorButton, &mut BackgroundColor), With<Button>>,
+    mut editor: ResMut<Editor>,
+    mut levels: ResMut<Levels>,
+    mut level_assets: ResMut<Assets<Level>>,
+    mut app_state: ResMut<NextState<AppState>>,
+) {
+    for (interaction, button, mut color) in &mut interaction_query {
+        // colors are set every frame so the selected tool stays highlighted
+        *color = match *interaction {
+            Interaction::Pressed | Interaction::Hovered => HOVER_COLOR.into(),
+            Interaction::None if button.action == EditorButtonAction::Tool(editor.tool) => {
+                SELECTED_COLOR.into()
+            }
+            Interaction::None => IDLE_COLOR.into(),
+        };
+
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        match button.action {
+            EditorButtonAction::Tool(tool) => editor.tool = tool,
+            EditorButtonAction::Adjust(field, delta) => {
+                adjust_field(&mut editor.level, field, delta)
+            }
+            EditorButtonAction::PrevLevel => {
+                if editor.number > 1 {
+                    editor.number -= 1;
+                    editor.level = load_level(&levels, &level_assets, editor.number);
+                    editor.status = String::new();
+                }
+            }
+            EditorButtonAction::NextLevel => {
+                // one past the last level is a new, empty one
+                if editor.number <= levels.0.len() {
+                    editor.number += 1;
+                    editor.level = load_level(&levels, &level_assets, editor.number);
+                    editor.status = String::new();
+                }
+            }
+            EditorButtonAction::New => {
+                editor.number = levels.0.len() + 1;
+                editor.level = Level::default();
+                editor.status = String::new();
+            }
+            EditorButtonAction::Save => match save_level(editor.number, &editor.level) {
+                Ok(path) => {
+                    // update the loaded copy too so Classic picks up the change
+                    let level = editor.level.clone();
+                    if let Some(handle) = levels.0.get(editor.number - 1) {
+                        if let Err(err) = level_assets.insert(handle, level) {
+                            error!("couldn't update level {}: {err}", editor.number);
+                            editor.status = format!("Saved, but couldn't reload: {}", err);
+                            continue;
+                        }
+                    } else {
+                        levels.0.push(level_assets.add(level));
+                    }
+                    editor.status = format!("Saved {}", path);
+                }
+                Err(err) => editor.status = format!("Couldn't save: {}", err),
+            },
+            EditorButtonAction::Playtest => {
+                commands.insert_resource(Playtest(editor.level.clone()));
+                app_state.set(AppState::Classic);
+            }
+            EditorButtonAction::Menu => app_state.set(AppState::Menu),
+        }
+    }
+}
+
+fn adjust_field(level: &mut Level, field: Field, delta: isize) {
+    let value = match field {
+        Field::StartI => &mut level.start_i,
+        Field::MaxI => &mut level.max_i,
+        Field::StartP => &mut level.start_p,
+        Field::MaxP => &mut level.max_p,
+        Field::TimeLimit => &mut level.time_limit,
+    };
+    *value = value.saturating_add_signed(delta);
+
+    // red alert can only add ships
+    match field {
+        Field::StartI => level.max_i = level.max_i.max(level.start_i),
+        Field::MaxI => level.start_i = level.start_i.min(level.max_i),
+        Field::StartP => level.max_p = level.max_p.max(level.start_p),
+        Field::MaxP => level.start_p = level.start_p.min(level.max_p),
+        Field::TimeLimit => level.time_limit = level.time_limit.max(5),
+    }
+}
+
+fn cursor_world_pos(
+    q_window: &Query<&Window, With<PrimaryWindow>>,
+    q_camera: &Query<(&Camera, &GlobalTransform), With<EditorCamera>>,
+) -> Option<Vec2> {
+    let window = q_window.single().ok()?;
+    let (camera, cam_trans) = q_camera.single().ok()?;
+    let cursor = window.cursor_position()?;
+    camera.viewport_to_world_2d(cam_trans, cursor).ok()
+}
+
+fn snap(pos: Vec2) -> Vec2 {
+    ((pos / GRID_SIZE).round() * GRID_SIZE).clamp(Vec2::splat(-ARENA_EDGE), Vec2::splat(ARENA_EDGE))
+}
+
+fn item_at(level: &Level, pos: Vec2) -> Option<Item> {
+    // rocks first since they're small enough to sit next to a star
+    for (i, rock) in level.rocks.iter().enumerate() {
+        if Vec2::new(rock.x, rock.y).distance(pos) < 30.0 {
+            return Some(Item::Rock(i));
+        }
+    }
+    for (i, star) in level.stars.iter().enumerate() {
+        if Vec2::new(star.x, star.y).distance(pos) < 100.0 {
+            return Some(Item::Star(i));
+        }
+    }
+    None
+}
+
+fn remove_item(level: &mut Level, item: Item) {
+    match item {
+        Item::Star(i) => {
+            level.stars.remove(i);
+        }
+        Item::Rock(i) => {
+            level.rocks.remove(i);
+        }
+    }
+}
+
+fn edit_level(
+    mouse: Res<ButtonInput<MouseButton>>,
+    q_window: Query<&Window, With<PrimaryWindow>>,
+    q_camera: Query<(&Camera, &GlobalTransform), With<EditorCamera>>,
+    q_interactions: Query<&Interaction>,
+    mut editor: ResMut<Editor>,
+) {
+    if mouse.just_released(MouseButton::Left) && editor.dragging.is_some() {
+        editor.dragging = None;
+    }
+
+    // clicks on the toolbar aren't for the map
+    if q_interactions.iter().any(|i| *i != Interaction::None) {
+        return;
+    }
+
+    let Some(pos) = cursor_world_pos(&q_window, &q_camera).map(snap) else {
+        return;
+    };
+
+    // deleting mid-drag would leave `dragging` pointing at the wrong item
+    if mouse.just_pressed(MouseButton::Right) && editor.dragging.is_none() {
+        if let Some(item) = item_at(&editor.level, pos) {
+            remove_item(&mut editor.level, item);
+        }
+        return;
+    }
+
+    match editor.tool {
+        Tool::Move => {
+            if mouse.just_pressed(MouseButton::Left) {
+                editor.dragging = item_at(&editor.level, pos);
+            }
+
+            // only touch the level when the item actually moves, so the
+            // scene isn't rebuilt every frame while the mouse is held
+            if mouse.pressed(MouseButton::Left) {
+                let dragging = editor.dragging;
+                let item_pos = match dragging {
+                    Some(Item::Star(i)) => editor.level.stars.get(i).map(|s| Vec2::new(s.x, s.y)),
+                    Some(Item::Rock(i)) => editor.level.rocks.get(i).map(|r| Vec2::new(r.x, r.y)),
+                    None => None,
+                };
+
+                if item_pos.is_some_and(|p| p != pos) {
+                    match dragging {
+                        Some(Item::Star(i)) => {
+                            if let Some(star) = editor.level.stars.get_mut(i) {
+                                (star.x, star.y) = (pos.x, pos.y);
+                            }
+                        }
+                        Some(Item::Rock(i)) => {
+                            if let Some(rock) = editor.level.rocks.get_mut(i) {
+                                (rock.x, rock.y) = (pos.x, pos.y);
+                            }
+                        }
+                        None => {}
+                    }
+                }
+            }
+        }
+        Tool::Erase => {
+            if mouse.just_pressed(MouseButton::Left) {
+                if let Some(item) = item_at(&editor.level, pos) {
+                    remove_item(&mut editor.level, item);
+                }
+            }
+        }
+        tool => {
+            if mouse.just_pressed(MouseButton::Left) {
+                // one thing per tile
+                if let Some(item) = item_at(&editor.level, pos) {
+                    remove_item(&mut editor.level, item);
+                }
+
+                let (x, y) = (pos.x, pos.y);
+                match tool {
+                    Tool::VertStar => editor.level.stars.push(Star { x, y, vert: true }),
+                    Tool::HorizStar => editor.level.stars.push(Star { x, y, vert: false }),
+                    Tool::Asteroid => editor.level.rocks.push(Rock { x, y, mine: false }),
+                    _ => editor.level.rocks.push(Rock { x, y, mine: true }),
+                }
+            }
+        }
+    }
+}
+
+fn move_camera(
+    time: Res<Time>,
+    kb: Res<ButtonInput<KeyCode>>,
+    scroll: Res<AccumulatedMouseScroll>,
+    mut q_camera: Query<(&mut Transform, &mut Projection), With<EditorCamera>>,
+) {
+    if let Ok((mut trans, mut projection)) = q_camera.single_mut() {
+        if let Projection::Orthographic(ortho) = &mut *projection {
+            let notches = match scroll.unit {
+                MouseScrollUnit::Line => scroll.delta.y,
+                MouseScrollUnit::Pixel => scroll.delta.y / 100.0,
+            };
+            if notches != 0.0 {
+                ortho.scale = (ortho.scale * (1.0 - notches * 0.1)).clamp(0.5, 8.0);
+            }
+
+            let mut dir = Vec2::ZERO;
+            if kb.any_pressed([KeyCode::KeyW, KeyCode::ArrowUp]) {
+                dir.y += 1.0;
+            }
+            if kb.any_pressed([KeyCode::KeyS, KeyCode::ArrowDown]) {
+                dir.y -= 1.0;
+            }
+            if kb.any_pressed([KeyCode::KeyA, KeyCode::ArrowLeft]) {
+                dir.x -= 1.0;
+            }
+            if kb.any_pressed([KeyCode::KeyD, KeyCode::ArrowRight]) {
+                dir.x += 1.0;
+            }
+
+            let pan = dir * PAN_SPEED * ortho.scale * time.delta_secs();
+            let pos = (trans.translation.truncate() + pan)
+                .clamp(Vec2::splat(-2500.0), Vec2::splat(2500.0));
+            trans.translation = pos.extend(trans.translation.z);
+        }
+    }
+}
+
+fn sync_items(
+    mut commands: Commands,
+    editor: Res<Editor>,
+    game_assets: Res<GameAssets>,
+    q_items: Query<Entity, With<EditorItem>>,
+) {
+    if !editor.is_changed() {
+        return;
+    }
+
+    for ent in &q_items {
+        commands.entity(ent).despawn();
+    }
+
+    for star in &editor.level.stars {
+        let texture = if star.vert {
+            game_assets.v_star.clone()
+        } else {
+            game_assets.h_star.clone()
+        };
+
+        commands
+            .spawn((
+                Sprite {
+                    image: texture,
+                    ..default()
+                },
+                Transform::from_xyz(star.x, star.y, 1.0),
+                EditorItem,
+                EditorNode,
+            ))
+            .with_children(|parent| {
+                // roughly where the star nodes end up
+                parent.spawn(
+                    ShapeBuilder::with(&shapes::Circle {
+                        radius: 144.0,
+                        center: Vec2::ZERO,
+                    })
+                    .fill(Color::srgba(0f32, 0.741, 0f32, 0.1))
+                    .stroke((Color::srgb(0f32, 0.741, 0f32), 2.0))
+                    .build(),
+                );
+            });
+    }
+
+    for rock in &editor.level.rocks {
+        let texture = if rock.mine {
+            game_assets.mine.clone()
+        } else {
+            game_assets.asteroid.clone()
+        };
+
+        commands.spawn((
+            Sprite {
+                image: texture,
+                ..default()
+            },
+            Transform::from_xyz(rock.x, rock.y, 2.0),
+            EditorItem,
+            EditorNode,
+        ));
+    }
+}
+
+fn update_labels(
+    editor: Res<Editor>,
+    levels: Res<Levels>,
+    mut q_labels: Query<(&mut Text, &EditorLabel)>,
+) {
+    if !editor.is_changed() {
+        return;
+    }
+
+    let level = &editor.level;
+    for (mut text, label) in &mut q_labels {
+        text.0 = match label {
+            EditorLabel::Field(Field::StartI) => format!("I-Type {}", level.start_i),
+            EditorLabel::Field(Field::MaxI) => format!("Max {}", level.max_i),
+            EditorLabel::Field(Field::StartP) => format!("P-Type {}", level.start_p),
+            EditorLabel::Field(Field::MaxP) => format!("Max {}", level.max_p),
+            EditorLabel::Field(Field::TimeLimit) => format!("Time {}", level.time_limit),
+            EditorLabel::LevelNumber if editor.number > levels.0.len() => {
+                format!("Level {} (new)", editor.number)
+            }
+            EditorLabel::LevelNumber => format!("Level {}", editor.number),
+            EditorLabel::Status => editor.status.clone(),
+        };
+    }
+}
+
+fn draw_grid(
+    mut gizmos: Gizmos,
+    editor: Res<Editor>,
+    q_window: Query<&Window, With<PrimaryWindow>>,
+    q_camera: Query<(&Camera, &GlobalTransform), With<EditorCamera>>,
+) {
+    gizmos.grid_2d(
+        Isometry2d::IDENTITY,
+        UVec2::splat(100),
+        Vec2::splat(GRID_SIZE),
+        Color::srgba(1.0, 1.0, 1.0, 0.04),
+    );
+
+    if let Some(pos) = cursor_world_pos(&q_window, &q_camera).map(snap) {
+        let color = match editor.tool {
+            Tool::Erase => Color::srgb(1.0, 0.2, 0.2),
+            _ => Color::WHITE,
+        };
+        gizmos.rect_2d(
+            Isometry2d::from_translation(pos),
+            Vec2::splat(GRID_SIZE),
+            color,
+        );
+    }
+}
+
+fn despawn_editor(mut commands: Commands, q_editor: Query<Entity, With<EditorNode>>) {
+    for ent in &q_editor {
+        commands.entity(ent).despawn();
+    }
+}
@@ -5,20 +5,26 @@ use bevy::{
 };
 use bevy_prototype_lyon::prelude::*;
 use bevy_rapier2d::prelude::*;
-use rand::{thread_rng, Rng};
-use std::{f32::consts::PI, time::Duration};
+use rand::Rng;
+use std::{collections::HashSet, f32::consts::PI, time::Duration};
 
 use super::{
+    announcer::{Announce, Callout},
     game::{
         // systems
         animation,
+        blink_pickup,
         bullet_timer,
         button_system,
         destroy_game,
         follow_camera,
         listen_explosion,
         move_enemy_ships,
+        move_missiles,
         player_input,
+        spawn_rock,
+        spawn_star,
+        star_hatch,
         star_node_shoot,
         update_minimap,
         world_to_minimap,
@@ -38,31 +44,87 @@ use super::{
         GameButtonAction,
         GameCamera,
         GameNode,
+        Hatch,
+        HatchState,
         IType,
+        KillEvent,
         LevelNode,
         MinimapCamera,
         MinimapPlayer,
-        MinimapStar,
         PType,
         Player,
         PlayerDeathEvent,
+        PlayerProjectile,
         SetupLevel,
         StarCore,
         StarNode,
+        PICKUP_LIFETIME,
     },
-    AppState, Atlas, GameAssets,
+    director::{spot_near_misses, Difficulty, Director, RecentDeaths},
+    field::{generate, Field, STAR_PLAYER_CLEARANCE},
+    powerup::{
+        collect_power_ups, maybe_drop_power_up, spawn_power_up_hud, update_power_ups, PowerUps,
+    },
+    highscores::{spawn_initials_entry, HighScores, Mode, PendingScore},
+    pause::{toggle_pause, PauseState},
+    music::{MusicCue, Track},
+    sfx::{Sfx, SfxEvent},
+    AppState, GameAssets,
 };
 
+// pickups dropped by the player's kills, worth bonus seconds
+const FIGHTER_DROP_CHANCE: f64 = 0.2;
+const FIGHTER_BONUS_SECONDS: usize = 5;
+const STAR_BONUS_SECONDS: usize = 15;
+
+// stars out at the start, the director takes it from there
+const STARTING_STARS: usize = 3;
+
+// most mines allowed out at once
+const MAX_MINES: usize = 15;
+// seconds before a fresh mine can hurt the player
+const MINE_ARMING_SECONDS: f32 = 1.5;
+// mines this far from the camera are cleaned up
+const MINE_DESPAWN_DISTANCE: f32 = 1500.0;
+
 #[derive(Component)]
 struct ScoreText;
 
+// P-Types drop a mine behind them every so often
+#[derive(Component)]
+struct Minelayer(Timer);
+
+#[derive(Component)]
+struct Mine {
+    arming: Timer,
+}
+
 #[derive(Component)]
 struct StarSpawnTimer(Timer);
 
+#[derive(Component)]
+struct Pickup {
+    seconds: usize,
+    lifetime: Timer,
+    // minimap dot
+    marker: Entity,
+}
+
+// the field for this run, kept around so Replay can use the same seed
+#[derive(Resource)]
+struct Arena {
+    seed: u64,
+    field: Field,
+    // stars are handed out from the field in order
+    next_star: usize,
+}
+
 #[derive(Resource)]
 struct Game {
     countdown: usize,
     setup: bool,
+    // from pickups, on top of the time survived
+    bonus_seconds: usize,
 }
 
 #[derive(Resource)]
@@ -84,10 +146,20 @@ impl Plugin for EndlessPlugin {
         app.insert_state(EndlessGameState::None)
             .add_message::<ExplosionEvent>()
             .add_message::<PlayerDeathEvent>()
+            .add_message::<KillEvent>()
             .add_message::<SetupLevel>()
             .insert_resource(GameStartSeconds(0.0))
+            .init_resource::<RecentDeaths>()
             .add_systems(OnEnter(AppState::Endless), setup_game)
-            .add_systems(OnExit(AppState::Endless), destroy_game)
+            .add_systems(OnExit(AppState::Endless), (destroy_game, end_run))
+            // Replay, OnExit and OnEnter don't run going from Endless to Endless
+            .add_systems(
+                OnTransition {
+                    exited: AppState::Endless,
+                    entered: AppState::Endless,
+                },
+                (destroy_game, setup_game).chain(),
+            )
             .add_systems(OnEnter(EndlessGameState::GameOver), setup_gameover)
             .add_systems(
                 Update,
@@ -97,21 +169,40 @@ impl Plugin for EndlessPlugin {
                 Update,
                 countdown.run_if(in_state(EndlessGameState::Countdown)),
             )
+            .add_systems(
+                Update,
+                toggle_pause.run_if(
+                    in_state(EndlessGameState::Countdown).or(in_state(EndlessGameState::Play)),
+                ),
+            )
             .add_systems(
                 Update,
                 (
                     player_input,
                     follow_camera,
                     check_collisions,
+                    score_kills.after(check_collisions).after(collect_power_ups),
                     bullet_timer,
                     spawn_ships_and_stars,
                     move_enemy_ships,
                     star_node_shoot,
+                    star_hatch,
+                    move_missiles,
                     star_update,
                     update_minimap,
                     update_score,
+                    lay_mines,
+                    update_mines,
+                    update_pickups,
+                    direct_difficulty,
+                    spot_near_misses,
                 )
-                    .run_if(in_state(EndlessGameState::Play)),
+                    .run_if(in_state(EndlessGameState::Play).and(in_state(PauseState::Running))),
+            )
+            .add_systems(
+                Update,
+                (collect_power_ups, update_power_ups)
+                    .run_if(in_state(EndlessGameState::Play).and(in_state(PauseState::Running))),
             )
             .add_systems(
                 Update,
@@ -131,7 +222,17 @@ fn setup_game(
     game_assets: Res<GameAssets>,
     mut game_state: ResMut<NextState<EndlessGameState>>,
     mut level_events: MessageWriter<SetupLevel>,
+    arena: Option<Res<Arena>>,
 ) {
+    // a fresh field unless this is a replay
+    let seed = arena.map_or_else(|| rand::rng().random::<u32>() as u64, |arena| arena.seed);
+    commands.insert_resource(Arena {
+        seed,
+        field: generate(seed),
+        next_star: 0,
+    });
+    commands.insert_resource(Director::default());
+
     let mut rapier_config = rapier_config.single_mut().unwrap();
     rapier_config.gravity = Vec2::ZERO;
 
@@ -227,6 +328,8 @@ fn setup_game(
         GameNode,
     ));
 
+    spawn_power_up_hud(&mut commands, &game_assets);
+
     // minimap player
     commands.spawn((
         ShapeBuilder::with(&shapes::Circle {
@@ -245,6 +348,7 @@ fn setup_game(
     commands.insert_resource(Game {
         countdown: 3,
         setup: false,
+        bonus_seconds: 0,
     });
 
     commands.spawn((
@@ -263,9 +367,23 @@ fn setup_game(
 
 fn setup_gameover(
     mut commands: Commands,
+    time: Res<Time>,
+    game: Res<Game>,
     game_assets: Res<GameAssets>,
+    game_start: Res<GameStartSeconds>,
+    high_scores: Res<HighScores>,
+    arena: Res<Arena>,
+    mut music_cue: ResMut<MusicCue>,
     q_camera: Query<&mut Transform, With<GameCamera>>,
 ) {
+    music_cue.0 = Some(Track::GameOver);
+
+    let seconds = (time.elapsed_secs() - game_start.0) as usize + game.bonus_seconds;
+    let new_high_score = high_scores.qualifies(Mode::Endless, seconds);
+    if new_high_score {
+        commands.insert_resource(PendingScore::new(Mode::Endless, seconds, 0));
+    }
+
     let camera = q_camera.single().unwrap();
     let trans = camera.translation + Vec3::new(0.0, 100.0, 50.0);
     let texture = game_assets.game_over.clone();
@@ -298,6 +416,51 @@ fn setup_gameover(
             GameNode,
         ))
         .with_children(|parent| {
+            parent.spawn((
+                Text::new(format!("Seed {}", arena.seed)),
+                TextColor(Color::srgb(0.58, 0.60, 0.69)),
+                TextFont {
+                    font: game_assets.font.clone(),
+                    font_size: 20.0,
+                    ..default()
+                },
+            ));
+
+            if new_high_score {
+                spawn_initials_entry(parent, &game_assets);
+            }
+
+            // same seed, same field
+            parent
+                .spawn((
+                    Button,
+                    Node {
+                        width: Val::Px(225.0),
+                        height: Val::Px(70.0),
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        ..default()
+                    },
+                    BorderRadius::all(Val::Px(10.0)),
+                    BackgroundColor(Color::BLACK),
+                    GameButton {
+                        action: GameButtonAction::ReplayEndless,
+                        idle_color: Color::srgb(0.15, 0.15, 0.15),
+                        hover_color: Color::srgb(0.25, 0.25, 0.25),
+                    },
+                ))
+                .with_children(|parent| {
+                    parent.spawn((
+                        Text::new("Replay"),
+                        TextColor(Color::srgb(0.9, 0.9, 0.9)),
+                        TextFont {
+                            font: game_assets.font.clone(),
+                            font_size: 30.0,
+                            ..default()
+                        },
+                    ));
+                });
+
             parent
                 .spawn((
                     Button,
@@ -330,6 +493,37 @@ fn setup_gameover(
         });
 }
 
+fn end_run(mut commands: Commands) {
+    commands.remove_resource::<Arena>();
+    commands.remove_resource::<Director>();
+}
+
+// the next star from the field whose spot isn't taken by a live one
+// or too close to the player
+fn spawn_next_star(
+    commands: &mut Commands,
+    game_assets: &GameAssets,
+    arena: &mut Arena,
+    taken: &[Vec2],
+    player: Vec2,
+) -> Option<Vec2> {
+    let n_stars = arena.field.stars.len();
+    for _ in 0..n_stars {
+        let star = arena.field.stars[arena.next_star % n_stars].clone();
+        arena.next_star = (arena.next_star + 1) % n_stars;
+
+        let pos = Vec2::new(star.x, star.y);
+        if pos.distance(player) > STAR_PLAYER_CLEARANCE
+            && taken.iter().all(|taken| taken.distance(pos) > 1.0)
+        {
+            spawn_star(commands, game_assets, star.x, star.y, star.vert);
+            return Some(pos);
+        }
+    }
+
+    None
+}
+
 fn setup_level(
     mut level_event: MessageReader<SetupLevel>,
     mut game: ResMut<Game>,
@@ -351,12 +545,28 @@ fn setup_level(
     }
 }
 
+fn direct_difficulty(
+    time: Res<Time>,
+    game_start: Res<GameStartSeconds>,
+    game_assets: Res<GameAssets>,
+    difficulties: Res<Assets<Difficulty>>,
+    deaths: Res<RecentDeaths>,
+    mut director: ResMut<Director>,
+) {
+    // keeps the starting caps until the file's loaded
+    if let Some(difficulty) = difficulties.get(&game_assets.difficulty) {
+        let now = time.elapsed_secs();
+        director.update(difficulty, &deaths, now - game_start.0, now);
+    }
+}
+
 fn update_score(
     time: Res<Time>,
+    game: Res<Game>,
     game_start: Res<GameStartSeconds>,
     mut q_level_text: Query<&mut Text, With<ScoreText>>,
 ) {
-    let seconds = (time.elapsed_secs() - game_start.0) as usize;
+    let seconds = (time.elapsed_secs() - game_start.0) as usize + game.bonus_seconds;
     let score = format!("{:02}:{:02}", seconds / 60, seconds % 60);
     for mut text in &mut q_level_text {
         text.0 = score.to_string();
@@ -375,11 +585,11 @@ fn countdown(
     mut q_camera: Query<&mut Transform, With<GameCamera>>,
     mut minimap: Query<&mut Camera, With<MinimapCamera>>,
     mut game_start: ResMut<GameStartSeconds>,
+    mut arena: ResMut<Arena>,
 ) {
     if let Ok(mut countdown) = q_countdown.single_mut() {
         countdown.0.tick(time.delta());
         if countdown.0.just_finished() {
-            println!("{:?}", game.countdown);
             if game.countdown == 3 {
                 minimap.single_mut().unwrap().is_active = true;
 
@@ -400,13 +610,14 @@ fn countdown(
                         one_time: false,
                     },
                     Player,
+                    PowerUps::default(),
                     RigidBody::Dynamic,
                     Collider::ball(28.0),
                     Ccd::enabled(),
                     Sensor,
                     CollisionGroups::new(
                         Group::from_bits_truncate(0b00000001),
-                        Group::from_bits_truncate(0b11001110),
+                        Group::from_bits_truncate(0b1111001110),
                     ),
                     CameraOffset(Vec3::ZERO),
                     Velocity::default(),
@@ -418,7 +629,20 @@ fn countdown(
                 if game.setup {
                     game.setup = false;
 
-                    // TODO initial spawn of stars and rocks
+                    for rock in &arena.field.rocks {
+                        spawn_rock(&mut commands, &game_assets, rock.x, rock.y, rock.mine);
+                    }
+
+                    let mut taken = Vec::new();
+                    for _ in 0..STARTING_STARS {
+                        taken.extend(spawn_next_star(
+                            &mut commands,
+                            &game_assets,
+                            &mut arena,
+                            &taken,
+                            Vec2::ZERO,
+                        ));
+                    }
                 }
 
                 if let Ok(mut cam_trans) = q_camera.single_mut() {
@@ -432,6 +656,7 @@ fn countdown(
                 for ent in &q_countdown_text {
                     commands.entity(ent).despawn();
                 }
+                commands.write_message(SfxEvent::new(Sfx::Countdown));
 
                 commands.spawn((
                     Sprite {
@@ -450,6 +675,7 @@ fn countdown(
             }
 
             if game.countdown == 0 {
+                commands.write_message(Announce(Callout::LevelStart));
                 game_start.0 = time.elapsed_secs();
                 game_state.set(EndlessGameState::Play);
                 q_player.single_mut().unwrap().linvel = Vec2::new(0.0, 400.0);
@@ -468,24 +694,21 @@ fn countdown(
 
 fn spawn_ships_and_stars(
     mut commands: Commands,
-    game_start: Res<GameStartSeconds>,
+    director: Res<Director>,
     game_assets: Res<GameAssets>,
     q_fighter: Query<Entity, With<EnemyShip>>,
-    q_stars: Query<Entity, With<StarCore>>,
+    q_stars: Query<&Transform, With<StarCore>>,
     q_cam_offest: Query<&CameraOffset>,
     mut q_spawn_timer: Query<&mut StarSpawnTimer>,
+    mut arena: ResMut<Arena>,
     time: Res<Time>,
 ) {
-    let seconds = (time.elapsed_secs() - game_start.0) as usize;
-    let max_fighters = ((seconds / 30) * 5) + 5;
-    let max_stars = ((seconds / 30) * 3) + 3;
-
     let mut fighter_count = 0;
     for _ in q_fighter.iter() {
         fighter_count += 1;
     }
 
-    if fighter_count < max_fighters {
+    if fighter_count < director.max_fighters {
         if let Ok(offset) = q_cam_offest.single() {
             let mut rng = rand::rng();
             let angle: f32 = rng.random_range(-PI..PI);
@@ -514,8 +737,8 @@ fn spawn_ships_and_stars(
                         target: None,
                         time_got_target: None,
                         // max_time_on_target: 3.0,
-                        speed: 250.0,
-                        turn_radius: 0.02,
+                        speed: 250.0 * director.fighter_speed,
+                        turn_radius: 0.02 * director.fighter_turn,
                     },
                     CollisionGroups::new(
                         Group::from_bits_truncate(0b0000100),
@@ -523,6 +746,10 @@ fn spawn_ships_and_stars(
                     ),
                     Explodable(ExplodableType::PType),
                     PType,
+                    Minelayer(Timer::from_seconds(
+                        rng.random_range(2.0..4.0),
+                        TimerMode::Repeating,
+                    )),
                     RenderLayers::layer(0),
                     LevelNode,
                     GameNode,
@@ -547,8 +774,8 @@ fn spawn_ships_and_stars(
                         target: None,
                         time_got_target: None,
                         // max_time_on_target: 0.25,
-                        speed: 300.0,
-                        turn_radius: 0.05,
+                        speed: 300.0 * director.fighter_speed,
+                        turn_radius: 0.05 * director.fighter_turn,
                     },
                     CollisionGroups::new(
                         Group::from_bits_truncate(0b0000100),
@@ -567,156 +794,176 @@ fn spawn_ships_and_stars(
     if let Ok(mut timer) = q_spawn_timer.single_mut() {
         timer.0.tick(time.delta());
         if timer.0.just_finished() {
-            let mut star_count = 0;
-            for _ in q_stars.iter() {
-                star_count += 1;
+            let taken: Vec<Vec2> = q_stars.iter().map(|t| t.translation.truncate()).collect();
+
+            let player = q_cam_offest.single().map_or(Vec2::ZERO, |offset| offset.0.truncate());
+            if taken.len() < director.max_stars {
+                spawn_next_star(&mut commands, &game_assets, &mut arena, &taken, player);
             }
 
-            let mut rng = rand::rng();
+            timer.0.set_duration(Duration::from_secs_f32(3.0));
+        }
+    }
+}
 
-            if star_count < max_stars {
-                // TODO avoid overlaps
-                // also maybe some buffer from the edge
-                let x = rng.random_range(-2400.0..=2400.0) as f32;
-                let y = rng.random_range(-2400.0..=2400.0) as f32;
-                let vert = rng.random_bool(0.5);
-
-                let marker = commands
-                    .spawn((
-                        ShapeBuilder::with(&shapes::Circle {
-                            radius: 7f32,
-                            center: Vec2::ZERO,
-                        })
-                        .fill(Color::srgb(0f32, 0.741, 0f32))
-                        .build(),
-                        Transform {
-                            translation: world_to_minimap(Vec3::new(x, y, 3.0)),
-                            ..default()
-                        },
-                        MinimapStar,
-                        RenderLayers::layer(1),
-                        LevelNode,
-                        GameNode,
-                    ))
-                    .id();
-
-                let star_config: (Handle<Image>, [(Vec3, Atlas); 6]) = if vert {
-                    (
-                        game_assets.v_star.clone(),
-                        [
-                            (Vec3::new(48.0, 96.0, 1.0), game_assets.star_node_v1.clone()),
-                            (Vec3::new(112.0, 0.0, 1.0), game_assets.star_node_v2.clone()),
-                            (
-                                Vec3::new(48.0, -96.0, 1.0),
-                                game_assets.star_node_v3.clone(),
-                            ),
-                            (
-                                Vec3::new(-48.0, -96.0, 1.0),
-                                game_assets.star_node_v4.clone(),
-                            ),
-                            (
-                                Vec3::new(-112.0, 0.0, 1.0),
-                                game_assets.star_node_v5.clone(),
-                            ),
-                            (
-                                Vec3::new(-48.0, 96.0, 1.0),
-                                game_assets.star_node_v6.clone(),
-                            ),
-                        ],
-                    )
-                } else {
-                    (
-                        game_assets.h_star.clone(),
-                        [
-                            (
-                                Vec3::new(96.0, -48.0, 1.0),
-                                game_assets.star_node_h1.clone(),
-                            ),
-                            (
-                                Vec3::new(0.0, -112.0, 1.0),
-                                game_assets.star_node_h2.clone(),
-                            ),
-                            (
-                                Vec3::new(-96.0, -48.0, 1.0),
-                                game_assets.star_node_h3.clone(),
-                            ),
-                            (
-                                Vec3::new(-96.0, 48.0, 1.0),
-                                game_assets.star_node_h4.clone(),
-                            ),
-                            (Vec3::new(0.0, 112.0, 1.0), game_assets.star_node_h5.clone()),
-                            (Vec3::new(96.0, 48.0, 1.0), game_assets.star_node_h6.clone()),
-                        ],
-                    )
-                };
-
-                commands
-                    .spawn((
-                        Sprite {
-                            image: star_config.0,
-                            ..default()
-                        },
-                        Transform::from_xyz(x, y, 1.0),
-                        RigidBody::Fixed,
-                        Collider::ball(20.0),
-                        StarCore(marker),
-                        CollisionGroups::new(
-                            Group::from_bits_truncate(0b00010000),
-                            Group::from_bits_truncate(0b00100000),
-                        ),
-                        Explodable(ExplodableType::StarCore),
-                        RenderLayers::layer(0),
-                        LevelNode,
-                        GameNode,
-                        Name::from("STAR"),
-                    ))
-                    .with_children(|parent| {
-                        for (pos, atlas) in star_config.1 {
-                            parent
-                                .spawn((
-                                    Sprite {
-                                        image: atlas.texture,
-                                        texture_atlas: Some(TextureAtlas::from(atlas.layout)),
-                                        ..default()
-                                    },
-                                    Transform {
-                                        translation: pos,
-                                        ..default()
-                                    },
-                                    RigidBody::Fixed,
-                                    Collider::ball(32.0),
-                                    StarNode(Timer::from_seconds(
-                                        thread_rng().gen_range(0.5..3.5),
-                                        TimerMode::Once,
-                                    )),
-                                    Explodable(ExplodableType::StarNode),
-                                    Sensor,
-                                    CollisionGroups::new(
-                                        Group::from_bits_truncate(0b0001000),
-                                        Group::from_bits_truncate(0b0100001),
-                                    ),
-                                    RenderLayers::layer(0),
-                                ))
-                                .with_children(|node| {
-                                    node.spawn(Collider::ball(300.0)).insert(Sensor);
-                                });
-                        }
-                    });
+fn lay_mines(
+    mut commands: Commands,
+    time: Res<Time>,
+    game_assets: Res<GameAssets>,
+    mut q_minelayers: Query<(&Transform, &mut Minelayer)>,
+    q_mines: Query<(), With<Mine>>,
+) {
+    let mut mine_count = q_mines.iter().count();
+    for (trans, mut minelayer) in q_minelayers.iter_mut() {
+        minelayer.0.tick(time.delta());
+        if !minelayer.0.just_finished() || mine_count >= MAX_MINES {
+            continue;
+        }
+        mine_count += 1;
+
+        // just behind the ship so it doesn't fly straight into it
+        let behind = trans.rotation.mul_vec3(Vec3::new(-60.0, 0.0, 0.0));
+
+        commands.spawn((
+            Sprite {
+                image: game_assets.mine.clone(),
+                color: Color::srgba(1.0, 1.0, 1.0, 0.5),
+                ..default()
+            },
+            Transform::from_xyz(trans.translation.x + behind.x, trans.translation.y + behind.y, 10.0),
+            RigidBody::Fixed,
+            Collider::ball(28.0),
+            Sensor,
+            // can be shot, but nothing else touches it until it's armed
+            CollisionGroups::new(
+                Group::from_bits_truncate(0b0000010),
+                Group::from_bits_truncate(0b0100000),
+            ),
+            Explodable(ExplodableType::Mine),
+            Mine {
+                arming: Timer::from_seconds(MINE_ARMING_SECONDS, TimerMode::Once),
+            },
+            RenderLayers::layer(0),
+            LevelNode,
+            GameNode,
+        ));
+    }
+}
+
+fn update_mines(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut q_mines: Query<(Entity, &Transform, &mut Mine, &mut Sprite)>,
+    q_camera: Query<&Transform, With<GameCamera>>,
+) {
+    let Ok(cam_trans) = q_camera.single() else {
+        return;
+    };
+
+    for (ent, trans, mut mine, mut sprite) in q_mines.iter_mut() {
+        let distance = trans
+            .translation
+            .truncate()
+            .distance(cam_trans.translation.truncate());
+        if distance > MINE_DESPAWN_DISTANCE {
+            commands.entity(ent).despawn();
+            continue;
+        }
+
+        mine.arming.tick(time.delta());
+        if mine.arming.just_finished() {
+            sprite.color = Color::WHITE;
+            commands.entity(ent).insert(CollisionGroups::new(
+                Group::from_bits_truncate(0b0000010),
+                Group::from_bits_truncate(0b1100101),
+            ));
+        }
+    }
+}
+
+fn spawn_pickup(commands: &mut Commands, game_assets: &GameAssets, pos: Vec2, seconds: usize) {
+    let marker = commands
+        .spawn((
+            ShapeBuilder::with(&shapes::Circle {
+                radius: 5f32,
+                center: Vec2::ZERO,
+            })
+            .fill(GOLD)
+            .build(),
+            Transform::from_translation(world_to_minimap(pos.extend(3.0))),
+            RenderLayers::layer(1),
+            LevelNode,
+            GameNode,
+        ))
+        .id();
+
+    commands.spawn((
+        Sprite {
+            image: game_assets.time_bonus.clone(),
+            ..default()
+        },
+        Transform::from_translation(pos.extend(5.0)),
+        RigidBody::Fixed,
+        Collider::ball(16.0),
+        Sensor,
+        CollisionGroups::new(
+            Group::from_bits_truncate(0b1000000000),
+            Group::from_bits_truncate(0b0000000001),
+        ),
+        Pickup {
+            seconds,
+            lifetime: Timer::from_seconds(PICKUP_LIFETIME, TimerMode::Once),
+            marker,
+        },
+        RenderLayers::layer(0),
+        LevelNode,
+        GameNode,
+    ));
+}
+
+fn update_pickups(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut game: ResMut<Game>,
+    rapier_context: ReadRapierContext,
+    mut q_pickups: Query<(Entity, &mut Pickup, &mut Visibility)>,
+    q_player: Query<Entity, With<Player>>,
+    mut sfx_events: MessageWriter<SfxEvent>,
+) {
+    let player = q_player.single().ok();
+    let context = rapier_context.single().unwrap();
+
+    for (ent, mut pickup, mut visibility) in q_pickups.iter_mut() {
+        let collected =
+            player.is_some_and(|player| context.intersection_pair(ent, player) == Some(true));
+
+        pickup.lifetime.tick(time.delta());
+        if collected || pickup.lifetime.is_finished() {
+            if collected {
+                game.bonus_seconds += pickup.seconds;
+                sfx_events.write(SfxEvent::new(Sfx::Pickup));
             }
 
-            timer.0.set_duration(Duration::from_secs_f32(3.0));
+            commands.entity(pickup.marker).despawn();
+            commands.entity(ent).despawn();
+            continue;
         }
+
+        blink_pickup(&pickup.lifetime, &mut visibility);
     }
 }
 
 fn listen_player_death_endless(
+    time: Res<Time>,
     mut events: MessageReader<PlayerDeathEvent>,
+    mut deaths: ResMut<RecentDeaths>,
     mut game_state: ResMut<NextState<EndlessGameState>>,
     mut q_mm_player: Query<&mut Transform, With<MinimapPlayer>>,
     mut minimap: Query<&mut Camera, With<MinimapCamera>>,
 ) {
     for _ in events.read() {
-        println!("Event in listen_player_death_endless");
+        deaths.0.push(time.elapsed_secs());
+
         let mut cam = minimap.single_mut().unwrap();
         cam.is_active = false;
 
@@ -730,9 +977,11 @@ fn listen_player_death_endless(
 
 fn star_update(
     mut commands: Commands,
+    game_assets: Res<GameAssets>,
     q_stars: Query<(Entity, &StarCore, &GlobalTransform, &Children)>,
     q_star_node: Query<&StarNode>,
     mut explosion_events: MessageWriter<ExplosionEvent>,
+    mut sfx_events: MessageWriter<SfxEvent>,
     mut q_star_timer: Query<&mut StarSpawnTimer>,
 ) {
     for (ent, star, trans, nodes) in q_stars.iter() {
@@ -752,7 +1001,17 @@ fn star_update(
 
             commands.entity(star.0).despawn();
             commands.entity(ent).despawn();
+            spawn_pickup(
+                &mut commands,
+                &game_assets,
+                trans.translation().truncate(),
+                STAR_BONUS_SECONDS,
+            );
 
+            sfx_events.write(SfxEvent::at(
+                Sfx::StarDestroyed,
+                trans.translation().truncate(),
+            ));
             explosion_events.write(ExplosionEvent {
                 size: ExplosionSize::Big,
                 x: trans.translation().x,
@@ -762,21 +1021,62 @@ fn star_update(
     }
 }
 
+// the director and the drops, for everything the player shoots down
+fn score_kills(
+    mut commands: Commands,
+    time: Res<Time>,
+    game_assets: Res<GameAssets>,
+    mut director: ResMut<Director>,
+    mut kill_events: MessageReader<KillEvent>,
+) {
+    let mut rng = rand::rng();
+    for kill in kill_events.read() {
+        if matches!(
+            kill.explodable,
+            ExplodableType::Rock | ExplodableType::Mine | ExplodableType::Laser
+        ) {
+            continue;
+        }
+
+        director.record_kill(time.elapsed_secs());
+        maybe_drop_power_up(&mut commands, &game_assets, &kill.explodable, kill.pos);
+
+        let bonus = match kill.explodable {
+            ExplodableType::StarCore => Some(STAR_BONUS_SECONDS),
+            ExplodableType::Figher | ExplodableType::IType | ExplodableType::PType
+                if rng.random_bool(FIGHTER_DROP_CHANCE) =>
+            {
+                Some(FIGHTER_BONUS_SECONDS)
+            }
+            _ => None,
+        };
+        if let Some(seconds) = bonus {
+            spawn_pickup(&mut commands, &game_assets, kill.pos, seconds);
+        }
+    }
+}
+
 fn check_collisions(
     mut commands: Commands,
     rapier_context: ReadRapierContext,
-    mut q_player: Query<(Entity, &GlobalTransform), With<Player>>,
+    mut q_player: Query<(Entity, &GlobalTransform, &mut PowerUps), With<Player>>,
     q_explodables: Query<(Entity, &GlobalTransform, &Explodable), With<Explodable>>,
     q_collidables: Query<(Entity, &GlobalTransform, &Collidable), With<Collidable>>,
     mut explosion_events: MessageWriter<ExplosionEvent>,
     mut player_death_events: MessageWriter<PlayerDeathEvent>,
+    mut kill_events: MessageWriter<KillEvent>,
     q_stars: Query<(Entity, &StarCore)>,
+    q_hatches: Query<&Hatch>,
+    q_player_lasers: Query<(), With<PlayerProjectile>>,
     mut q_star_timer: Query<&mut StarSpawnTimer>,
     mut q_star_node_textures: Query<&mut Sprite, With<StarNode>>,
 ) {
     // maybe not the best, if player is gone, do we still want explo-explo actions?
-    if let Ok((player, p_trans)) = q_player.single_mut() {
-        let mut p = true;
+    if let Ok((player, p_trans, mut power_ups)) = q_player.single_mut() {
+        // however many things hit the player this frame, it's one hit
+        let mut player_hit = false;
+        // every pair shows up twice below, only count each kill once
+        let mut killed = HashSet::new();
         let context = rapier_context.single().unwrap();
         for (e_ent, e_trans, explo) in q_explodables.iter() {
             // STEP 1 -- Explodable-Explodable interactions
@@ -788,6 +1088,20 @@ fn check_collisions(
 
                 if context.intersection_pair(e_ent, e_ent2) == Some(true) {
                     for (ent, trans, exp) in [(e_ent, e_trans, explo), (e_ent2, e_trans2, explo2)] {
+                        // the core only takes hits while its hatch is open
+                        if q_hatches.get(ent).is_ok_and(|hatch| hatch.state != HatchState::Open) {
+                            continue;
+                        }
+
+                        let other = if ent == e_ent { e_ent2 } else { e_ent };
+                        if q_player_lasers.contains(other) && killed.insert(ent) {
+                            kill_events.write(KillEvent {
+                                explodable: exp.0,
+                                pos: trans.translation().truncate(),
+                                formation: None,
+                            });
+                        }
+
                         match exp.0 {
                             ExplodableType::StarNode => {
                                 if let Ok(mut sprite) = q_star_node_textures.get_mut(e_ent) {
@@ -812,25 +1126,14 @@ fn check_collisions(
                                 });
                             }
                             ExplodableType::StarCore => {
-                                for (star_ent, star) in q_stars.iter() {
-                                    if star_ent == ent {
-                                        commands.entity(star.0).despawn();
-                                        break;
-                                    }
-                                }
-
-                                if let Ok(mut timer) = q_star_timer.single_mut() {
-                                    if timer.0.is_finished() {
-                                        timer.0.set_duration(Duration::from_secs_f32(3.0));
-                                    }
-                                }
-                                commands.entity(ent).despawn();
-
-                                explosion_events.write(ExplosionEvent {
-                                    size: ExplosionSize::Big,
-                                    x: trans.translation().x,
-                                    y: trans.translation().y,
-                                });
+                                destroy_star(
+                                    &mut commands,
+                                    &q_stars,
+                                    &mut q_star_timer,
+                                    ent,
+                                    trans,
+                                    &mut explosion_events,
+                                );
                             }
                             ExplodableType::Laser => {
                                 commands.entity(ent).despawn();
@@ -858,19 +1161,25 @@ fn check_collisions(
                             }
                         }
                     }
+                    // same as a laser, it only gets through an open hatch
+                    ExplodableType::StarCore => {
+                        if q_hatches.get(e_ent).is_ok_and(|hatch| hatch.state == HatchState::Open) {
+                            destroy_star(
+                                &mut commands,
+                                &q_stars,
+                                &mut q_star_timer,
+                                e_ent,
+                                e_trans,
+                                &mut explosion_events,
+                            );
+                        }
+                    }
                     _ => commands.entity(e_ent).despawn(),
                 }
 
-                commands.entity(player).despawn();
-                player_death_events.write(PlayerDeathEvent {});
+                player_hit = true;
 
-                explosion_events.write(ExplosionEvent {
-                    size: ExplosionSize::Small,
-                    x: p_trans.translation().x,
-                    y: p_trans.translation().y,
-                });
-
-                if explo.0 != ExplodableType::Laser {
+                if !matches!(explo.0, ExplodableType::Laser | ExplodableType::StarCore) {
                     explosion_events.write(ExplosionEvent {
                         size: ExplosionSize::Small,
                         x: e_trans.translation().x,
@@ -879,7 +1188,7 @@ fn check_collisions(
                 }
             }
 
-            // STEP 3 -- Explodable-Collidable & Player-Collidable Interactions
+            // STEP 3 -- Explodable-Collidable Interactions
             for (c_ent, _, __) in q_collidables.iter() {
                 if context.intersection_pair(e_ent, c_ent) == Some(true) {
                     match explo.0 {
@@ -889,21 +1198,53 @@ fn check_collisions(
                         _ => {}
                     }
                 }
+            }
+        }
 
-                if p && context.intersection_pair(c_ent, player) == Some(true) {
-                    commands.entity(player).despawn();
-                    player_death_events.write(PlayerDeathEvent {});
-                    p = false;
+        // STEP 4 -- Player-Collidable Interactions
+        player_hit |= q_collidables
+            .iter()
+            .any(|(c_ent, _, _)| context.intersection_pair(c_ent, player) == Some(true));
 
-                    explosion_events.write(ExplosionEvent {
-                        size: ExplosionSize::Small,
-                        x: p_trans.translation().x,
-                        y: p_trans.translation().y,
-                    });
-                }
-            }
+        // a shield takes the hit instead
+        if player_hit && !power_ups.absorb_hit() {
+            commands.entity(player).despawn();
+            player_death_events.write(PlayerDeathEvent {});
+
+            explosion_events.write(ExplosionEvent {
+                size: ExplosionSize::Small,
+                x: p_trans.translation().x,
+                y: p_trans.translation().y,
+            });
+        }
+    }
+}
+
+// the core goes up and takes its star with it, the next one is hurried along
+fn destroy_star(
+    commands: &mut Commands,
+    q_stars: &Query<(Entity, &StarCore)>,
+    q_star_timer: &mut Query<&mut StarSpawnTimer>,
+    core: Entity,
+    trans: &GlobalTransform,
+    explosion_events: &mut MessageWriter<ExplosionEvent>,
+) {
+    if let Ok((_, star)) = q_stars.get(core) {
+        commands.entity(star.0).despawn();
+    }
+
+    if let Ok(mut timer) = q_star_timer.single_mut() {
+        if timer.0.is_finished() {
+            timer.0.set_duration(Duration::from_secs_f32(3.0));
         }
     }
+    commands.entity(core).despawn();
+
+    explosion_events.write(ExplosionEvent {
+        size: ExplosionSize::Big,
+        x: trans.translation().x,
+        y: trans.translation().y,
+    });
 }
 
 /*
@@ -911,24 +1252,21 @@ fn check_collisions(
     . filter
     & member and filter
 
-             8 7 6 5 4 3 2 1
-    Player   . .     . . . *
-     Rocks     . .     . * .
-   Fighter     . .     & . .
-  StarNode       .   *     .
-  StarCore       . *
-   P-Laser   .   * . . . .
-   S-Laser     *       . . .
-Collidable   *   .         .
-
-fighter dropping mines?
-
-seconds  max_fighters  max_stars
-   0-30             5          3
-  30-60            10          6
-  60-90            15          9
-    etc
-
-  ((sec // 30) * 5) + 5
-  ((sec // 30) * 3) + 3
+             10 9 8 7 6 5 4 3 2 1
+    Player    . . . .     . . . *
+     Rocks          . .     . * .
+   Fighter          . .     & . .
+  StarNode            .   *     .
+  StarCore            . *
+   P-Laser      . .   * . . . .
+   S-Laser          *       . . .
+   Missile      *     .         .
+Collidable        *   .         .
+    Pickup    *                 .
+
+mines are Rocks, but only filter P-Laser until they're armed
+pickups are time bonuses and power-ups
+
+spawn caps and enemy speeds follow the curves in
+assets/endless.difficulty.ron, see director.rs
 */
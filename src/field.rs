@@ -0,0 +1,101 @@
+use bevy::prelude::*;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::f32::consts::PI;
+
+use super::levels::{Rock, Star};
+
+// lays out the Endless arena from a seed, the same seed always
+// gives the same field so a run can be replayed
+
+// the arena wall sits at +-2500, leave some room in front of it
+const HALF_SIZE: f32 = 2300.0;
+const CLUSTERS: usize = 14;
+const CLUSTER_RADIUS: f32 = 300.0;
+const MINE_CHANCE: f64 = 0.25;
+// rocks are 56px across
+const ROCK_SPACING: f32 = 70.0;
+// the player starts at the origin
+const PLAYER_CLEARANCE: f32 = 500.0;
+// also kept around wherever the player is when a star respawns
+pub const STAR_PLAYER_CLEARANCE: f32 = 800.0;
+const STAR_SPACING: f32 = 700.0;
+// nodes reach out about 150px, plus room to fly between
+const STAR_ROCK_SPACING: f32 = 300.0;
+// most stars the field will hold, they're handed out in order
+const STARS: usize = 24;
+// attempts at a free spot before giving up on a star or rock
+const MAX_TRIES: usize = 50;
+
+pub struct Field {
+    pub rocks: Vec<Rock>,
+    pub stars: Vec<Star>,
+}
+
+pub fn generate(seed: u64) -> Field {
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    // asteroid fields first, with the odd mine mixed in
+    let mut rocks: Vec<Rock> = Vec::new();
+    for _ in 0..CLUSTERS {
+        let center = random_point(&mut rng, HALF_SIZE - CLUSTER_RADIUS);
+        if center.length() < PLAYER_CLEARANCE + CLUSTER_RADIUS {
+            continue;
+        }
+
+        let size = rng.random_range(5..=10);
+        for _ in 0..size {
+            let spot = (0..MAX_TRIES)
+                .map(|_| {
+                    let angle = rng.random_range(-PI..PI);
+                    let dist = CLUSTER_RADIUS * rng.random_range(0.0f32..1.0).sqrt();
+                    center + Vec2::from_angle(angle) * dist
+                })
+                .find(|pos| {
+                    rocks
+                        .iter()
+                        .all(|rock| pos.distance(Vec2::new(rock.x, rock.y)) >= ROCK_SPACING)
+                });
+
+            if let Some(pos) = spot {
+                rocks.push(Rock {
+                    x: pos.x,
+                    y: pos.y,
+                    mine: rng.random_bool(MINE_CHANCE),
+                });
+            }
+        }
+    }
+
+    // then stars wherever there's room
+    let mut stars: Vec<Star> = Vec::new();
+    for _ in 0..STARS {
+        let spot = (0..MAX_TRIES)
+            .map(|_| random_point(&mut rng, HALF_SIZE))
+            .find(|pos| {
+                pos.length() >= STAR_PLAYER_CLEARANCE
+                    && stars
+                        .iter()
+                        .all(|star| pos.distance(Vec2::new(star.x, star.y)) >= STAR_SPACING)
+                    && rocks
+                        .iter()
+                        .all(|rock| pos.distance(Vec2::new(rock.x, rock.y)) >= STAR_ROCK_SPACING)
+            });
+
+        if let Some(pos) = spot {
+            stars.push(Star {
+                x: pos.x,
+                y: pos.y,
+                vert: rng.random_bool(0.5),
+            });
+        }
+    }
+
+    Field { rocks, stars }
+}
+
+fn random_point(rng: &mut StdRng, half_size: f32) -> Vec2 {
+    Vec2::new(
+        rng.random_range(-half_size..=half_size),
+        rng.random_range(-half_size..=half_size),
+    )
+}
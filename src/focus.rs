@@ -0,0 +1,132 @@
+use bevy::{prelude::*, ui::UiSystems, window::CursorMoved};
+
+use super::highscores::PendingScore;
+
+// lets a gamepad get around the buttons on whatever screen is up,
+// the focused button shows as hovered and A presses it
+
+// how far the stick has to be pushed to move the focus
+const NAV_DEADZONE: f32 = 0.5;
+// in UI space, where y goes down
+const NAV_BUTTONS: [(GamepadButton, Vec2); 4] = [
+    (GamepadButton::DPadUp, Vec2::NEG_Y),
+    (GamepadButton::DPadDown, Vec2::Y),
+    (GamepadButton::DPadLeft, Vec2::NEG_X),
+    (GamepadButton::DPadRight, Vec2::X),
+];
+
+#[derive(Resource, Default)]
+struct MenuFocus {
+    button: Option<Entity>,
+    // the stick has to go back to the middle between moves
+    stick_held: bool,
+}
+
+pub struct MenuFocusPlugin;
+impl Plugin for MenuFocusPlugin {
+    fn build(&self, app: &mut App) {
+        // after bevy works out what the mouse is over, so the focus wins.
+        // the pad is busy while initials are being entered
+        app.init_resource::<MenuFocus>().add_systems(
+            PreUpdate,
+            focus_buttons
+                .after(UiSystems::Focus)
+                .run_if(not(resource_exists::<PendingScore>)),
+        );
+    }
+}
+
+fn focus_buttons(
+    mut focus: ResMut<MenuFocus>,
+    mut cursor: MessageReader<CursorMoved>,
+    q_gamepads: Query<&Gamepad>,
+    mut q_buttons: Query<
+        (Entity, &UiGlobalTransform, &InheritedVisibility, &mut Interaction),
+        With<Button>,
+    >,
+) {
+    // the mouse takes over again as soon as it moves
+    if cursor.read().count() > 0 {
+        focus.button = None;
+    }
+
+    // the screen it was on is gone, or something is covering it
+    if focus.button.is_some_and(|button| {
+        q_buttons
+            .get(button)
+            .map_or(true, |(_, _, visibility, _)| !visibility.get())
+    }) {
+        focus.button = None;
+    }
+
+    let mut nav = None;
+    let mut stick = None;
+    let mut press = false;
+    for gamepad in &q_gamepads {
+        for (button, dir) in NAV_BUTTONS {
+            if gamepad.just_pressed(button) {
+                nav = Some(dir);
+            }
+        }
+
+        let s = gamepad.left_stick();
+        if s.length() >= NAV_DEADZONE {
+            stick = Some(Vec2::new(s.x, -s.y).normalize());
+        }
+
+        press |= gamepad.just_pressed(GamepadButton::South);
+    }
+
+    match stick {
+        Some(dir) if !focus.stick_held => {
+            nav = nav.or(Some(dir));
+            focus.stick_held = true;
+        }
+        None => focus.stick_held = false,
+        _ => {}
+    }
+
+    if let Some(dir) = nav {
+        let visible: Vec<(Entity, Vec2)> = q_buttons
+            .iter()
+            .filter(|(_, _, visibility, _)| visibility.get())
+            .map(|(ent, trans, _, _)| (ent, trans.translation))
+            .collect();
+
+        let from = focus
+            .button
+            .and_then(|button| q_buttons.get(button).ok())
+            .map(|(_, trans, _, _)| trans.translation);
+
+        focus.button = match from {
+            // nothing focused yet, start at the top
+            None => visible
+                .iter()
+                .min_by(|a, b| a.1.y.total_cmp(&b.1.y).then(a.1.x.total_cmp(&b.1.x)))
+                .map(|(ent, _)| *ent),
+            // the closest button that way, favouring ones straight ahead
+            Some(from) => visible
+                .iter()
+                .filter(|(_, pos)| (*pos - from).dot(dir) > 1.0)
+                .min_by(|a, b| {
+                    let score = |pos: Vec2| {
+                        let d = pos - from;
+                        d.dot(dir) + 2.0 * d.perp_dot(dir).abs()
+                    };
+                    score(a.1).total_cmp(&score(b.1))
+                })
+                .map(|(ent, _)| *ent)
+                .or(focus.button),
+        };
+    }
+
+    if let Some(button) = focus.button {
+        if let Ok((_, _, _, mut interaction)) = q_buttons.get_mut(button) {
+            interaction.set_if_neq(if press {
+                Interaction::Pressed
+            } else {
+                Interaction::Hovered
+            });
+        }
+    }
+}
@@ -0,0 +1,151 @@
+use bevy::{camera::visibility::RenderLayers, prelude::*};
+use bevy_rapier2d::prelude::*;
+
+use super::{
+    game::{EnemyShip, Explodable, ExplodableType, GameNode, LevelNode},
+    GameAssets,
+};
+
+// a leader with fewer escorts than this isn't much of a formation
+const MIN_ESCORTS: usize = 4;
+// escort slots relative to the leader, which flies along +x,
+// filled in order so smaller groups stay a tidy V
+const ESCORT_OFFSETS: [Vec2; 6] = [
+    Vec2::new(-60.0, 60.0),
+    Vec2::new(-60.0, -60.0),
+    Vec2::new(-120.0, 120.0),
+    Vec2::new(-120.0, -120.0),
+    Vec2::new(-180.0, 180.0),
+    Vec2::new(-180.0, -180.0),
+];
+const LEADER_SPEED: f32 = 200.0;
+// how hard escorts pull back into their slot
+const ESCORT_CATCH_UP: f32 = 4.0;
+const ESCORT_MAX_SPEED: f32 = 450.0;
+
+// the group as a whole, lives until every member is gone
+#[derive(Component)]
+pub struct Formation {
+    pub size: usize,
+    // members destroyed by the player
+    pub shot: usize,
+}
+
+#[derive(Component)]
+pub struct FormationMember(pub Entity);
+
+#[derive(Component)]
+pub struct Escort {
+    pub leader: Entity,
+    pub offset: Vec2,
+}
+
+fn spawn_ship(commands: &mut Commands, game_assets: &GameAssets, trans: Transform) -> Entity {
+    commands
+        .spawn((
+            Sprite {
+                image: game_assets.i_type.clone(),
+                ..default()
+            },
+            trans,
+            Velocity::default(),
+            RigidBody::Dynamic,
+            Collider::ball(26.0),
+            Sensor,
+            // unlike lone fighters these don't run into each other
+            CollisionGroups::new(
+                Group::from_bits_truncate(0b0000100),
+                Group::from_bits_truncate(0b1100011),
+            ),
+            Explodable(ExplodableType::Figher),
+            RenderLayers::layer(0),
+            LevelNode,
+            GameNode,
+        ))
+        .id()
+}
+
+// spawns a leader with `escorts` ships in tow at `pos`, heading along `angle`
+pub fn spawn_formation(
+    commands: &mut Commands,
+    game_assets: &GameAssets,
+    pos: Vec2,
+    angle: f32,
+    escorts: usize,
+) {
+    let escorts = escorts.clamp(MIN_ESCORTS, ESCORT_OFFSETS.len());
+    let formation = commands
+        .spawn((
+            Formation {
+                size: escorts + 1,
+                shot: 0,
+            },
+            LevelNode,
+            GameNode,
+        ))
+        .id();
+
+    let rotation = Quat::from_rotation_z(angle);
+    let leader = spawn_ship(
+        commands,
+        game_assets,
+        Transform {
+            translation: pos.extend(10.0),
+            rotation,
+            ..default()
+        },
+    );
+    commands.entity(leader).insert((
+        EnemyShip {
+            target: None,
+            time_got_target: None,
+            speed: LEADER_SPEED,
+            turn_radius: 0.03,
+        },
+        FormationMember(formation),
+    ));
+
+    for offset in &ESCORT_OFFSETS[..escorts] {
+        let escort = spawn_ship(
+            commands,
+            game_assets,
+            Transform {
+                translation: (pos + Vec2::from_angle(angle).rotate(*offset)).extend(10.0),
+                rotation,
+                ..default()
+            },
+        );
+        commands.entity(escort).insert((
+            Escort {
+                leader,
+                offset: *offset,
+            },
+            FormationMember(formation),
+        ));
+    }
+}
+
+pub fn move_escorts(
+    mut commands: Commands,
+    mut q_escorts: Query<(Entity, &Escort, &mut Transform, &mut Velocity), Without<EnemyShip>>,
+    q_leaders: Query<(&Transform, &Velocity), With<EnemyShip>>,
+) {
+    for (ent, escort, mut trans, mut vel) in q_escorts.iter_mut() {
+        let Ok((leader_trans, leader_vel)) = q_leaders.get(escort.leader) else {
+            // leader's gone, everyone for themselves
+            commands.entity(ent).remove::<Escort>().insert(EnemyShip {
+                target: None,
+                time_got_target: None,
+                speed: 300.0,
+                turn_radius: 0.05,
+            });
+            continue;
+        };
+
+        let slot = leader_trans.translation.truncate()
+            + leader_trans.rotation.mul_vec3(escort.offset.extend(0.0)).truncate();
+        let correction = (slot - trans.translation.truncate()) * ESCORT_CATCH_UP;
+        vel.linvel = (leader_vel.linvel + correction).clamp_length_max(ESCORT_MAX_SPEED);
+        trans.rotation = leader_trans.rotation;
+    }
+}
@@ -1,8 +1,12 @@
-use super::{AppState, GameAssets};
+use super::{
+    director::Director, input::PlayerInput, pause::PauseState, powerup::PowerUps,
+    settings::spawn_settings_panel, sfx::{Sfx, SfxEvent}, AppState, Atlas, GameAssets,
+};
 use bevy::{
-    audio::AudioSink,
+    camera::visibility::RenderLayers,
     prelude::*,
 };
+use bevy_prototype_lyon::prelude::*;
 use bevy_rapier2d::prelude::*;
 use rand::Rng;
 use std::{
@@ -12,6 +16,38 @@ use std::{
 
 const TWO_PI: f32 = 2.0 * PI;
 
+// how long the star core hatch takes to open or close
+const HATCH_MOVE_SECONDS: f32 = 0.45;
+const HATCH_OPEN_SECONDS: f32 = 2.5;
+// only launch missiles at a player this close to the core
+const MISSILE_RANGE: f32 = 600.0;
+const MISSILE_SPEED: f32 = 260.0;
+// radians per second
+const MISSILE_TURN_RATE: f32 = 2.0;
+// between the front lasers of a spread shot
+const SPREAD_ANGLE: f32 = 0.2;
+const PLAYER_SPEED: f32 = 400.0;
+// seconds between shots while fire is held, rapid fire is quicker
+const FIRE_INTERVAL: f32 = 0.25;
+// lasers the player can have out at once
+const MAX_PLAYER_LASERS: usize = 16;
+// world units per minimap pixel, and the minimap's size on screen
+pub const MINIMAP_SCALE: f32 = 20.0;
+pub const MINIMAP_SIZE: f32 = 250.0;
+// time bonuses and power-ups, they blink for the last few seconds
+pub const PICKUP_LIFETIME: f32 = 10.0;
+const PICKUP_WARNING: f32 = 3.0;
+// enemy ship steering
+const SHIP_RADIUS: f32 = 28.0;
+// slows down inside this distance of its target, but never below the minimum
+const ARRIVE_DISTANCE: f32 = 150.0;
+const MIN_ARRIVE_SPEED: f32 = 0.6;
+const SEPARATION_DISTANCE: f32 = 90.0;
+const SEPARATION_WEIGHT: f32 = 1.5;
+// how far ahead (in seconds of flying) it looks for rocks
+const AVOID_SECONDS: f32 = 0.8;
+const AVOID_WEIGHT: f32 = 3.0;
+
 //
 // enums
 //
@@ -26,19 +62,52 @@ pub enum ExplosionSize {
     Big,
 }
 
-#[derive(PartialEq)]
+#[derive(Clone, Copy, PartialEq)]
 pub enum ExplodableType {
     Rock,
+    Mine,
     Figher,
     StarCore,
     StarNode,
     Laser,
     IType,
     PType,
+    EType,
+    Missile,
+}
+
+impl ExplodableType {
+    // points for shooting one of these, star cores and
+    // spies are worth more depending on the mode
+    pub fn points(&self) -> usize {
+        match self {
+            ExplodableType::Rock => 10,
+            ExplodableType::Mine => 20,
+            ExplodableType::Figher | ExplodableType::IType => 50,
+            ExplodableType::PType => 60,
+            ExplodableType::StarNode => 200,
+            ExplodableType::Missile => 100,
+            ExplodableType::StarCore | ExplodableType::EType | ExplodableType::Laser => 0,
+        }
+    }
+}
+
+#[derive(PartialEq)]
+pub enum HatchState {
+    Closed,
+    Opening,
+    Open,
+    Closing,
 }
 
 pub enum GameButtonAction {
     ReturnToMenu,
+    ReturnToEditor,
+    ReplayEndless,
+    Resume,
+    // the same mode again from the start
+    Restart,
+    Settings,
 }
 
 //
@@ -89,6 +158,16 @@ pub struct GameCamera;
 #[derive(Component)]
 pub struct GameNode;
 
+// on the star core, the core can only be shot while it's open
+#[derive(Component)]
+pub struct Hatch {
+    pub state: HatchState,
+    pub timer: Timer,
+}
+
+#[derive(Component)]
+pub struct HatchSprite;
+
 #[derive(Component)]
 pub struct IType;
 
@@ -98,6 +177,9 @@ pub struct PType;
 #[derive(Component)]
 pub struct LevelNode;
 
+#[derive(Component)]
+pub struct Missile;
+
 #[derive(Component)]
 pub struct MinimapCamera;
 
@@ -113,9 +195,6 @@ pub struct Player;
 #[derive(Component)]
 pub struct PlayerProjectile;
 
-#[derive(Component)]
-pub struct SoundEffect;
-
 #[derive(Component)]
 pub struct Projectile(pub Timer);
 
@@ -138,6 +217,15 @@ pub struct ExplosionEvent {
 #[derive(Message)]
 pub struct PlayerDeathEvent;
 
+// the player shot (or bombed) something down, each mode scores it its own way
+#[derive(Message)]
+pub struct KillEvent {
+    pub explodable: ExplodableType,
+    pub pos: Vec2,
+    // the formation it flew in, it's gone by the time this is read
+    pub formation: Option<Entity>,
+}
+
 #[derive(Message)]
 pub struct SetupLevel;
 
@@ -194,6 +282,8 @@ pub fn bullet_timer(
 }
 
 pub fn button_system(
+    mut commands: Commands,
+    game_assets: Res<GameAssets>,
     mut interaction_query: Query<
         (
             &Interaction,
@@ -203,12 +293,24 @@ pub fn button_system(
         ),
         (Changed<Interaction>, With<Button>),
     >,
+    state: Res<State<AppState>>,
     mut app_state: ResMut<NextState<AppState>>,
+    mut pause_state: ResMut<NextState<PauseState>>,
 ) {
     for (interaction, button, mut color, mut border_color) in &mut interaction_query {
         match *interaction {
             Interaction::Pressed => match button.action {
                 GameButtonAction::ReturnToMenu => app_state.set(AppState::Menu),
+                GameButtonAction::ReturnToEditor => app_state.set(AppState::Editor),
+                GameButtonAction::ReplayEndless => app_state.set(AppState::Endless),
+                GameButtonAction::Resume => pause_state.set(PauseState::Running),
+                GameButtonAction::Restart => {
+                    pause_state.set(PauseState::Running);
+                    app_state.set(*state.get());
+                }
+                GameButtonAction::Settings => {
+                    spawn_settings_panel(&mut commands, &game_assets, false);
+                }
             },
             Interaction::Hovered => {
                 *color = button.hover_color.into();
@@ -292,59 +394,112 @@ pub fn listen_explosion(
     }
 }
 
+// steers every enemy ship towards its target while keeping clear of the
+// other ships and of whatever would blow it up. all of it is per second,
+// so it flies the same at any frame rate
 pub fn move_enemy_ships(
-    mut query: Query<(&mut Velocity, &mut Transform, &mut EnemyShip)>,
+    rapier_context: ReadRapierContext,
+    mut query: Query<(Entity, &mut Velocity, &mut Transform, &mut EnemyShip)>,
     q_player: Query<&CameraOffset>,
+    q_collidables: Query<(), With<Collidable>>,
+    q_explodables: Query<&Explodable>,
     time: Res<Time>,
 ) {
-    if let Ok(player_pos) = q_player.single() {
-        // TODO can they avoid rocks to some degree?
-        for (mut vel, mut trans, mut ship) in query.iter_mut() {
-            if let Some(target) = ship.target {
-                let angle = f32::atan2(
-                    target.y - trans.translation.y,
-                    target.x - trans.translation.x,
-                );
-
-                let (axis, mut rot) = trans.rotation.to_axis_angle();
-                rot = (axis * rot).z;
-
-                // does it need to rotate?
-                if (angle - rot).abs() > 0.05 {
-                    let a = (angle + TWO_PI) % (TWO_PI);
-                    let r = (rot + TWO_PI) % (TWO_PI);
-
-                    let mut diff = a - r;
-                    if diff.abs() > PI {
-                        diff += TWO_PI;
-                    }
+    let Ok(player_pos) = q_player.single() else {
+        return;
+    };
+    let context = rapier_context.single().unwrap();
+
+    // the walls, dead star nodes, rocks and mines
+    let is_obstacle = |ent: Entity| {
+        q_collidables.contains(ent)
+            || q_explodables.get(ent).is_ok_and(|explodable| {
+                explodable.0 == ExplodableType::Rock || explodable.0 == ExplodableType::Mine
+            })
+    };
+    let ship_shape = Collider::ball(SHIP_RADIUS);
+
+    let ships: Vec<(Entity, Vec2)> = query
+        .iter()
+        .map(|(ent, _, trans, _)| (ent, trans.translation.truncate()))
+        .collect();
+
+    for (ent, mut vel, mut trans, mut ship) in query.iter_mut() {
+        // picked and steered for in the same frame, so it never stops to think
+        let target = match ship.target {
+            Some(target) => target,
+            None => {
+                let mut rng = rand::rng();
+                let offset =
+                    Vec2::new(rng.random_range(-100.0..100.0), rng.random_range(-100.0..100.0));
+                let target = player_pos.0.truncate() + offset;
+                ship.target = Some(target);
+                ship.time_got_target = Some(time.elapsed_secs());
+                target
+            }
+        };
 
-                    // which way to rotate?
-                    if diff.is_sign_positive() {
-                        trans.rotate_z(ship.turn_radius);
-                    } else {
-                        trans.rotate_z(-ship.turn_radius);
-                    }
-                }
+        let pos = trans.translation.truncate();
+        let (axis, rot) = trans.rotation.to_axis_angle();
+        let heading = Vec2::from_angle((axis * rot).z);
 
-                if trans.translation.truncate().distance(target) < 10.0 {
-                    ship.target = None;
-                } else {
-                    vel.linvel = Vec2::from_angle(rot) * ship.speed;
-                }
+        if pos.distance(target) < 10.0 {
+            ship.target = None;
+            continue;
+        }
 
-                // find a new target if it's searched to long
-                if let Some(target_time) = ship.time_got_target {
-                    if time.elapsed_secs() - target_time > 0.5 {
-                        ship.target = None;
-                        ship.time_got_target = None;
-                    }
-                }
+        // seek, easing off on the way in (arrive)
+        let to_target = target - pos;
+        let arrive = (to_target.length() / ARRIVE_DISTANCE).clamp(MIN_ARRIVE_SPEED, 1.0);
+        let mut steer = to_target.normalize();
+
+        // separation
+        for (other, other_pos) in &ships {
+            let away = pos - *other_pos;
+            let distance = away.length();
+            if *other == ent || distance >= SEPARATION_DISTANCE || distance == 0.0 {
+                continue;
+            }
+            steer += away / distance * SEPARATION_WEIGHT * (1.0 - distance / SEPARATION_DISTANCE);
+        }
+
+        // obstacle avoidance, sliding off to whichever side the obstacle
+        // faces, harder the closer it is
+        let filter = QueryFilter::new()
+            .exclude_collider(ent)
+            .predicate(&is_obstacle);
+        if let Some((_, hit)) = context.cast_shape(
+            pos,
+            0.0,
+            heading * ship.speed,
+            &ship_shape,
+            ShapeCastOptions::with_max_time_of_impact(AVOID_SECONDS),
+            filter,
+        ) {
+            // normal1 is on the obstacle (the collider that was hit), pointing
+            // back out at the ship. normal2 is on the ship's own shape
+            let normal = hit.details.map_or(-heading, |details| details.normal1);
+            let side = if heading.perp().dot(normal) >= 0.0 {
+                heading.perp()
             } else {
-                let mut rng = rand::rng();
-                let offset = Vec2::new(rng.random_range(-100.0..100.0), rng.random_range(-100.0..100.0));
-                ship.target = Some(player_pos.0.truncate() + offset);
-                ship.time_got_target = Some(time.elapsed_secs());
+                -heading.perp()
+            };
+            let urgency = 1.0 - hit.time_of_impact / AVOID_SECONDS;
+            steer += (normal + side) * AVOID_WEIGHT * urgency;
+        }
+
+        // turn_radius is how far it turns in a 60th of a second
+        let max_turn = ship.turn_radius * 60.0 * time.delta_secs();
+        let diff = heading.angle_to(steer.normalize_or(heading));
+        let angle = heading.to_angle() + diff.clamp(-max_turn, max_turn);
+        trans.rotation = Quat::from_rotation_z(angle);
+        vel.linvel = Vec2::from_angle(angle) * ship.speed * arrive;
+
+        // find a new target if it's searched to long
+        if let Some(target_time) = ship.time_got_target {
+            if time.elapsed_secs() - target_time > 0.5 {
+                ship.target = None;
+                ship.time_got_target = None;
             }
         }
     }
@@ -352,116 +507,83 @@ pub fn move_enemy_ships(
 
 pub fn player_input(
     mut commands: Commands,
-    kb: Res<ButtonInput<KeyCode>>,
+    time: Res<Time>,
+    mut input: ResMut<PlayerInput>,
     game_assets: Res<GameAssets>,
-    mut player: Query<(&mut Transform, &mut Velocity, &mut CameraOffset), With<Player>>,
+    mut player: Query<(&mut Transform, &mut Velocity, &mut CameraOffset, &PowerUps), With<Player>>,
+    q_lasers: Query<(), With<PlayerProjectile>>,
+    mut sfx_events: MessageWriter<SfxEvent>,
 ) {
-    if let Ok((mut trans, mut vel, mut offset)) = player.single_mut() {
-        if let Some(keycode) = kb.get_just_pressed().last() {
-            let mut rot: f32 = -1.0;
-            match keycode {
-                KeyCode::KeyW => {
-                    vel.linvel = Vec2::new(0.0, 400.0);
-                    rot = 0.0;
-                }
-                KeyCode::KeyA => {
-                    vel.linvel = Vec2::new(-400.0, 0.0);
-                    rot = FRAC_PI_2;
-                }
-                KeyCode::KeyS => {
-                    vel.linvel = Vec2::new(0.0, -400.0);
-                    rot = PI;
-                }
-                KeyCode::KeyD => {
-                    vel.linvel = Vec2::new(400.0, 0.0);
-                    rot = 3.0 * FRAC_PI_2;
-                }
+    if let Ok((mut trans, mut vel, mut offset, power_ups)) = player.single_mut() {
+        // the ship keeps flying the last way it was pointed
+        if let Some(dir) = input.take_heading() {
+            vel.linvel = dir * PLAYER_SPEED;
+            // the sprite faces up
+            trans.rotation = Quat::from_rotation_z(dir.to_angle() - FRAC_PI_2);
+        }
 
-                KeyCode::Enter => {
-                    let texture1: Handle<Image>;
-                    let texture2: Handle<Image>;
-                    if vel.linvel.abs().x > 0f32 {
-                        texture1 = game_assets.h_laser.clone();
-                        texture2 = game_assets.h_laser.clone();
-                    } else {
-                        texture1 = game_assets.v_laser.clone();
-                        texture2 = game_assets.v_laser.clone();
-                    }
+        let interval = power_ups.fire_interval().unwrap_or(FIRE_INTERVAL);
+        let room = q_lasers.iter().count() < MAX_PLAYER_LASERS;
+        if input.fire(time.delta_secs(), interval, room) {
+            let texture = game_assets.laser.clone();
 
-                    // commands.spawn((
-                    //     AudioBundle {
-                    //         source: game_assets.laser_sound.clone(),
-                    //         settings: PlaybackSettings {
-                    //             volume: Volume::new(0.25),
-                    //             ..default()
-                    //         },
-                    //         ..default()
-                    //     },
-                    //     SoundEffect,
-                    //     GameNode,
-                    // ));
-
-                    commands.spawn((
-                        Sprite {
-                            image: texture1,
-                            ..default()
-                        },
-                        Transform::from_xyz(trans.translation.x, trans.translation.y, 1.0),
-                        RigidBody::Dynamic,
-                        Ccd::enabled(),
-                        Collider::ball(5.0),
-                        Sensor,
-                        Explodable(ExplodableType::Laser),
-                        PlayerProjectile,
-                        Projectile(Timer::from_seconds(5.0, TimerMode::Once)),
-                        CollisionGroups::new(
-                            Group::from_bits_truncate(0b00100000),
-                            Group::from_bits_truncate(0b10011110),
-                        ),
-                        Velocity {
-                            linvel: vel.linvel * 3.0,
-                            ..default()
-                        },
-                        LevelNode,
-                        GameNode,
-                    ));
+            sfx_events.write(SfxEvent::new(Sfx::Laser));
 
-                    commands.spawn((
-                        Sprite {
-                            image: texture2,
-                            ..default()
-                        },
-                        Transform::from_xyz(trans.translation.x, trans.translation.y, 1.0),
-                        RigidBody::Dynamic,
-                        Ccd::enabled(),
-                        Collider::ball(5.0),
-                        Sensor,
-                        Explodable(ExplodableType::Laser),
-                        PlayerProjectile,
-                        Projectile(Timer::from_seconds(5.0, TimerMode::Once)),
-                        CollisionGroups::new(
-                            Group::from_bits_truncate(0b00100000),
-                            Group::from_bits_truncate(0b10011110),
-                        ),
-                        Velocity {
-                            linvel: vel.linvel * -2.5,
-                            ..default()
-                        },
-                        LevelNode,
-                        GameNode,
-                    ));
+            let spread = power_ups.spread();
+            if spread == 0 {
+                spawn_laser(&mut commands, &texture, trans.translation, vel.linvel * 3.0);
+            } else {
+                // fan the front lasers out around the usual one
+                let front = spread - 1;
+                for i in 0..front {
+                    let angle = (i as f32 - (front - 1) as f32 / 2.0) * SPREAD_ANGLE;
+                    spawn_laser(
+                        &mut commands,
+                        &texture,
+                        trans.translation,
+                        Vec2::from_angle(angle).rotate(vel.linvel * 3.0),
+                    );
                 }
-                _ => {}
-            }
-            if rot >= 0.0 {
-                trans.rotation = Quat::from_rotation_z(rot);
             }
+            spawn_laser(&mut commands, &texture, trans.translation, vel.linvel * -2.5);
         }
 
         offset.0 = trans.translation;
     }
 }
 
+fn spawn_laser(commands: &mut Commands, texture: &Handle<Image>, pos: Vec3, linvel: Vec2) {
+    commands.spawn((
+        Sprite {
+            image: texture.clone(),
+            ..default()
+        },
+        Transform {
+            translation: Vec3::new(pos.x, pos.y, 1.0),
+            // the sprite points up
+            rotation: Quat::from_rotation_z(linvel.to_angle() - FRAC_PI_2),
+            ..default()
+        },
+        RigidBody::Dynamic,
+        Ccd::enabled(),
+        Collider::ball(5.0),
+        Sensor,
+        Explodable(ExplodableType::Laser),
+        PlayerProjectile,
+        Projectile(Timer::from_seconds(5.0, TimerMode::Once)),
+        CollisionGroups::new(
+            Group::from_bits_truncate(0b00100000),
+            Group::from_bits_truncate(0b110011110),
+        ),
+        Velocity {
+            linvel,
+            ..default()
+        },
+        LevelNode,
+        GameNode,
+    ));
+}
+
 pub fn star_node_shoot(
     mut commands: Commands,
     rapier_context: ReadRapierContext,
@@ -469,7 +591,12 @@ pub fn star_node_shoot(
     mut q_nodes: Query<(&GlobalTransform, &mut StarNode, &Children)>,
     mut q_player: Query<(Entity, &GlobalTransform), With<Player>>,
     game_assets: Res<GameAssets>,
+    director: Option<Res<Director>>,
+    mut sfx_events: MessageWriter<SfxEvent>,
 ) {
+    // Endless speeds them up as it goes
+    let interval_scale = director.map_or(1.0, |director| director.node_fire_interval);
+
     if let Ok((p_ent, p_trans)) = q_player.single_mut() {
         let context = rapier_context.single().unwrap();
         for (trans, mut node, children) in q_nodes.iter_mut() {
@@ -478,8 +605,9 @@ pub fn star_node_shoot(
                     node.0.tick(time.delta());
                     if node.0.is_finished() {
                         let mut rng = rand::rng();
-                        node.0
-                            .set_duration(Duration::from_secs_f32(rng.random_range(1.0..4.0)));
+                        node.0.set_duration(Duration::from_secs_f32(
+                            rng.random_range(1.0..4.0) * interval_scale,
+                        ));
                         node.0.reset();
 
                         let vel = (p_trans.translation().truncate()
@@ -487,6 +615,10 @@ pub fn star_node_shoot(
                         .normalize()
                             * 150.0;
 
+                        sfx_events.write(SfxEvent::at(
+                            Sfx::StarNodeLaser,
+                            trans.translation().truncate(),
+                        ));
                         commands.spawn((
                             Sprite {
                                 image: game_assets.star_node_laser.texture.clone(),
@@ -525,20 +657,283 @@ pub fn star_node_shoot(
     }
 }
 
-pub fn despawn_finished_sound_effects(
+pub fn spawn_star(commands: &mut Commands, game_assets: &GameAssets, x: f32, y: f32, vert: bool) {
+    let marker = commands
+        .spawn((
+            ShapeBuilder::with(&shapes::Circle {
+                radius: 7f32,
+                center: Vec2::ZERO,
+            })
+            .fill(Color::srgb(0f32, 0.741, 0f32))
+            .build(),
+            Transform {
+                translation: world_to_minimap(Vec3::new(x, y, 3.0)),
+                ..default()
+            },
+            MinimapStar,
+            RenderLayers::layer(1),
+            LevelNode,
+            GameNode,
+        ))
+        .id();
+
+    let star_config: (Handle<Image>, Atlas, [(Vec3, Atlas); 6]) = if vert {
+        (
+            game_assets.v_star.clone(),
+            game_assets.star_hatch_v.clone(),
+            [
+                (Vec3::new(48.0, 96.0, 1.0), game_assets.star_node_v1.clone()),
+                (Vec3::new(112.0, 0.0, 1.0), game_assets.star_node_v2.clone()),
+                (Vec3::new(48.0, -96.0, 1.0), game_assets.star_node_v3.clone()),
+                (Vec3::new(-48.0, -96.0, 1.0), game_assets.star_node_v4.clone()),
+                (Vec3::new(-112.0, 0.0, 1.0), game_assets.star_node_v5.clone()),
+                (Vec3::new(-48.0, 96.0, 1.0), game_assets.star_node_v6.clone()),
+            ],
+        )
+    } else {
+        (
+            game_assets.h_star.clone(),
+            game_assets.star_hatch_h.clone(),
+            [
+                (Vec3::new(96.0, -48.0, 1.0), game_assets.star_node_h1.clone()),
+                (Vec3::new(0.0, -112.0, 1.0), game_assets.star_node_h2.clone()),
+                (Vec3::new(-96.0, -48.0, 1.0), game_assets.star_node_h3.clone()),
+                (Vec3::new(-96.0, 48.0, 1.0), game_assets.star_node_h4.clone()),
+                (Vec3::new(0.0, 112.0, 1.0), game_assets.star_node_h5.clone()),
+                (Vec3::new(96.0, 48.0, 1.0), game_assets.star_node_h6.clone()),
+            ],
+        )
+    };
+
+    let mut rng = rand::rng();
+
+    commands
+        .spawn((
+            Sprite {
+                image: star_config.0,
+                ..default()
+            },
+            Transform::from_xyz(x, y, 1.0),
+            RigidBody::Fixed,
+            Collider::ball(20.0),
+            StarCore(marker),
+            Hatch {
+                state: HatchState::Closed,
+                timer: Timer::from_seconds(rng.random_range(2.0..8.0), TimerMode::Once),
+            },
+            CollisionGroups::new(
+                Group::from_bits_truncate(0b00010000),
+                Group::from_bits_truncate(0b00100000),
+            ),
+            Explodable(ExplodableType::StarCore),
+            RenderLayers::layer(0),
+            LevelNode,
+            GameNode,
+            Name::from("STAR"),
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Sprite {
+                    image: star_config.1.texture,
+                    texture_atlas: Some(TextureAtlas::from(star_config.1.layout)),
+                    ..default()
+                },
+                Transform::from_xyz(0.0, 0.0, 0.5),
+                HatchSprite,
+                RenderLayers::layer(0),
+            ));
+
+            for (pos, atlas) in star_config.2 {
+                parent
+                    .spawn((
+                        Sprite {
+                            image: atlas.texture,
+                            texture_atlas: Some(TextureAtlas::from(atlas.layout)),
+                            ..default()
+                        },
+                        Transform {
+                            translation: pos,
+                            ..default()
+                        },
+                        RigidBody::Fixed,
+                        Collider::ball(32.0),
+                        StarNode(Timer::from_seconds(
+                            rng.random_range(0.5..3.5),
+                            TimerMode::Once,
+                        )),
+                        Explodable(ExplodableType::StarNode),
+                        Sensor,
+                        CollisionGroups::new(
+                            Group::from_bits_truncate(0b0001000),
+                            Group::from_bits_truncate(0b0100001),
+                        ),
+                        RenderLayers::layer(0),
+                    ))
+                    .with_children(|node| {
+                        node.spawn(Collider::ball(300.0)).insert(Sensor);
+                    });
+            }
+        });
+}
+
+pub fn spawn_rock(commands: &mut Commands, game_assets: &GameAssets, x: f32, y: f32, mine: bool) {
+    let (texture, explodable) = if mine {
+        (game_assets.mine.clone(), ExplodableType::Mine)
+    } else {
+        (game_assets.asteroid.clone(), ExplodableType::Rock)
+    };
+
+    commands.spawn((
+        Sprite {
+            image: texture,
+            ..default()
+        },
+        Transform {
+            translation: Vec3::new(x, y, 10.0),
+            ..default()
+        },
+        RigidBody::Fixed,
+        Collider::ball(28.0),
+        Sensor,
+        CollisionGroups::new(
+            Group::from_bits_truncate(0b0000010),
+            Group::from_bits_truncate(0b1100101),
+        ),
+        Explodable(explodable),
+        RenderLayers::layer(0),
+        LevelNode,
+        GameNode,
+    ));
+}
+
+// opens and closes the star core hatches, launching
+// a missile at the player each time one opens
+pub fn star_hatch(
     mut commands: Commands,
-    query: Query<(Entity, &AudioSink), With<SoundEffect>>,
+    time: Res<Time>,
+    game_assets: Res<GameAssets>,
+    mut q_hatches: Query<(&GlobalTransform, &mut Hatch, &Children)>,
+    mut q_hatch_sprites: Query<&mut Sprite, With<HatchSprite>>,
+    q_player: Query<&GlobalTransform, With<Player>>,
+) {
+    for (trans, mut hatch, children) in q_hatches.iter_mut() {
+        hatch.timer.tick(time.delta());
+
+        if hatch.timer.is_finished() {
+            let mut rng = rand::rng();
+            let (state, seconds) = match hatch.state {
+                HatchState::Closed => (HatchState::Opening, HATCH_MOVE_SECONDS),
+                HatchState::Opening => (HatchState::Open, HATCH_OPEN_SECONDS),
+                HatchState::Open => (HatchState::Closing, HATCH_MOVE_SECONDS),
+                HatchState::Closing => (HatchState::Closed, rng.random_range(4.0..8.0)),
+            };
+            hatch.state = state;
+            hatch.timer = Timer::from_seconds(seconds, TimerMode::Once);
+
+            if hatch.state == HatchState::Open {
+                if let Ok(p_trans) = q_player.single() {
+                    let pos = trans.translation().truncate();
+                    let to_player = p_trans.translation().truncate() - pos;
+                    if to_player.length() < MISSILE_RANGE {
+                        spawn_missile(&mut commands, &game_assets, pos, to_player.to_angle());
+                    }
+                }
+            }
+        }
+
+        // last frame is fully open
+        let opened = hatch.timer.fraction();
+        let index = match hatch.state {
+            HatchState::Closed => 0,
+            HatchState::Opening => 1 + (opened * 2.0).min(1.0) as usize,
+            HatchState::Open => 3,
+            HatchState::Closing => 2 - (opened * 2.0).min(1.0) as usize,
+        };
+
+        for child in children.iter() {
+            if let Ok(mut sprite) = q_hatch_sprites.get_mut(child) {
+                if let Some(atlas) = &mut sprite.texture_atlas {
+                    atlas.index = index;
+                }
+            }
+        }
+    }
+}
+
+fn spawn_missile(commands: &mut Commands, game_assets: &GameAssets, pos: Vec2, angle: f32) {
+    commands.spawn((
+        Sprite {
+            image: game_assets.missile.clone(),
+            ..default()
+        },
+        Transform {
+            translation: pos.extend(5.0),
+            rotation: Quat::from_rotation_z(angle),
+            ..default()
+        },
+        RigidBody::Dynamic,
+        Ccd::enabled(),
+        Collider::ball(8.0),
+        Sensor,
+        Explodable(ExplodableType::Missile),
+        Missile,
+        Projectile(Timer::from_seconds(6.0, TimerMode::Once)),
+        CollisionGroups::new(
+            Group::from_bits_truncate(0b100000000),
+            Group::from_bits_truncate(0b000100001),
+        ),
+        Velocity {
+            linvel: Vec2::from_angle(angle) * MISSILE_SPEED,
+            ..default()
+        },
+        RenderLayers::layer(0),
+        LevelNode,
+        GameNode,
+    ));
+}
+
+// missiles turn towards the player, but only so fast
+pub fn move_missiles(
+    time: Res<Time>,
+    mut q_missiles: Query<(&mut Transform, &mut Velocity), With<Missile>>,
+    q_player: Query<&GlobalTransform, With<Player>>,
 ) {
-    for (entity, sink) in query.iter() {
-        if sink.empty() {
-            // playback ended
-            commands.entity(entity).despawn();
+    let Ok(p_trans) = q_player.single() else {
+        return;
+    };
+
+    for (mut trans, mut vel) in q_missiles.iter_mut() {
+        let heading = vel.linvel.to_angle();
+        let wanted = (p_trans.translation().truncate() - trans.translation.truncate()).to_angle();
+
+        let mut diff = (wanted - heading) % TWO_PI;
+        if diff > PI {
+            diff -= TWO_PI;
+        } else if diff < -PI {
+            diff += TWO_PI;
         }
+
+        let max_turn = MISSILE_TURN_RATE * time.delta_secs();
+        let angle = heading + diff.clamp(-max_turn, max_turn);
+        vel.linvel = Vec2::from_angle(angle) * MISSILE_SPEED;
+        trans.rotation = Quat::from_rotation_z(angle);
+    }
+}
+
+// blink when it's about to go
+pub fn blink_pickup(lifetime: &Timer, visibility: &mut Visibility) {
+    if lifetime.remaining_secs() < PICKUP_WARNING {
+        let blink = (lifetime.elapsed_secs() * 8.0) as usize;
+        *visibility = if blink.is_multiple_of(2) {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
     }
 }
 
 pub fn world_to_minimap(world_pos: Vec3) -> Vec3 {
-    world_pos / Vec3::new(20.0, 20.0, 1.0)
+    world_pos / Vec3::new(MINIMAP_SCALE, MINIMAP_SCALE, 1.0)
 }
 
 pub fn update_minimap(
@@ -0,0 +1,491 @@
+use bevy::{
+    input::{
+        keyboard::{Key, KeyboardInput},
+        ButtonState,
+    },
+    prelude::*,
+};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    game::{button_system, GameButton, GameButtonAction},
+    storage, AppState, GameAssets,
+};
+
+// entries kept per mode
+const MAX_SCORES: usize = 10;
+const STORAGE_KEY: &str = "high_scores";
+// what a gamepad can pick from, the same characters the keyboard takes
+const LETTERS: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Mode {
+    Classic,
+    Endless,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct HighScore {
+    pub initials: String,
+    // points in Classic, seconds survived in Endless
+    pub score: usize,
+    // only used by Classic
+    pub level: usize,
+    pub date: String,
+}
+
+#[derive(Resource, Default, Serialize, Deserialize)]
+pub struct HighScores {
+    classic: Vec<HighScore>,
+    endless: Vec<HighScore>,
+}
+
+impl HighScores {
+    fn table(&self, mode: Mode) -> &Vec<HighScore> {
+        match mode {
+            Mode::Classic => &self.classic,
+            Mode::Endless => &self.endless,
+        }
+    }
+
+    fn table_mut(&mut self, mode: Mode) -> &mut Vec<HighScore> {
+        match mode {
+            Mode::Classic => &mut self.classic,
+            Mode::Endless => &mut self.endless,
+        }
+    }
+
+    pub fn qualifies(&self, mode: Mode, score: usize) -> bool {
+        let table = self.table(mode);
+        score > 0 && (table.len() < MAX_SCORES || table.iter().any(|s| score > s.score))
+    }
+
+    fn insert(&mut self, mode: Mode, entry: HighScore) {
+        let table = self.table_mut(mode);
+        // ties go to whoever got there first
+        let i = table.partition_point(|s| s.score >= entry.score);
+        table.insert(i, entry);
+        table.truncate(MAX_SCORES);
+    }
+}
+
+// a finished run waiting on the player's initials
+#[derive(Resource)]
+pub struct PendingScore {
+    pub mode: Mode,
+    pub score: usize,
+    pub level: usize,
+    initials: String,
+    // the letter a gamepad is picking for the next slot, not added yet
+    letter: Option<char>,
+}
+
+impl PendingScore {
+    pub fn new(mode: Mode, score: usize, level: usize) -> Self {
+        PendingScore {
+            mode,
+            score,
+            level,
+            initials: String::new(),
+            letter: None,
+        }
+    }
+}
+
+#[derive(Component)]
+struct InitialsText;
+
+#[derive(Component)]
+struct HighScoresNode;
+
+pub struct HighScoresPlugin;
+impl Plugin for HighScoresPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(storage::load::<HighScores>(STORAGE_KEY).unwrap_or_default())
+            .add_systems(
+                Update,
+                enter_initials.run_if(resource_exists::<PendingScore>),
+            )
+            // leaving the game over screen early still keeps the score
+            .add_systems(OnExit(AppState::Classic), save_pending_score)
+            .add_systems(OnExit(AppState::Endless), save_pending_score)
+            .add_systems(
+                OnTransition {
+                    exited: AppState::Endless,
+                    entered: AppState::Endless,
+                },
+                save_pending_score,
+            )
+            .add_systems(OnEnter(AppState::HighScores), setup_high_scores)
+            .add_systems(
+                Update,
+                (button_system, back_to_menu).run_if(in_state(AppState::HighScores)),
+            )
+            .add_systems(OnExit(AppState::HighScores), despawn_high_scores);
+    }
+}
+
+// shown on the game over screen when the run made the table
+pub fn spawn_initials_entry(parent: &mut ChildSpawnerCommands, game_assets: &GameAssets) {
+    parent.spawn((
+        Text::new("New high score! Initials: ___"),
+        TextColor(Color::srgb(1.0, 0.85, 0.0)),
+        TextFont {
+            font: game_assets.font.clone(),
+            font_size: 20.0,
+            ..default()
+        },
+        InitialsText,
+    ));
+}
+
+// typed, or picked with a gamepad: up and down change the letter,
+// right or A adds it, left or B takes one back, and A on an empty slot
+// is done
+fn enter_initials(
+    mut commands: Commands,
+    mut keys: MessageReader<KeyboardInput>,
+    q_gamepads: Query<&Gamepad>,
+    mut pending: ResMut<PendingScore>,
+    mut high_scores: ResMut<HighScores>,
+    mut q_text: Query<&mut Text, With<InitialsText>>,
+) {
+    let mut done = false;
+    for key in keys.read() {
+        if key.state != ButtonState::Pressed {
+            continue;
+        }
+
+        match &key.logical_key {
+            Key::Character(c) => {
+                pending.letter = None;
+                for c in c.chars().filter(|c| c.is_ascii_alphanumeric()) {
+                    if pending.initials.len() < 3 {
+                        pending.initials.push(c.to_ascii_uppercase());
+                    }
+                }
+            }
+            Key::Backspace => {
+                pending.initials.pop();
+            }
+            Key::Enter if !pending.initials.is_empty() => done = true,
+            _ => {}
+        }
+    }
+
+    for gamepad in &q_gamepads {
+        if gamepad.just_pressed(GamepadButton::DPadUp) {
+            pending.letter = Some(cycle_letter(pending.letter, 1));
+        }
+        if gamepad.just_pressed(GamepadButton::DPadDown) {
+            pending.letter = Some(cycle_letter(pending.letter, -1));
+        }
+
+        let add = gamepad.just_pressed(GamepadButton::DPadRight)
+            || gamepad.just_pressed(GamepadButton::South);
+        if add {
+            match pending.letter.take() {
+                Some(c) if pending.initials.len() < 3 => pending.initials.push(c),
+                None if gamepad.just_pressed(GamepadButton::South)
+                    && !pending.initials.is_empty() =>
+                {
+                    done = true
+                }
+                _ => {}
+            }
+        }
+
+        let back = gamepad.just_pressed(GamepadButton::DPadLeft)
+            || gamepad.just_pressed(GamepadButton::East);
+        if back && pending.letter.take().is_none() {
+            pending.initials.pop();
+        }
+    }
+
+    // no slot left to pick for
+    if pending.initials.len() >= 3 && pending.letter.is_some() {
+        pending.letter = None;
+    }
+
+    if done {
+        save_score(&pending, &mut high_scores);
+        commands.remove_resource::<PendingScore>();
+
+        for mut text in &mut q_text {
+            text.0 = format!("Saved as {}", pending.initials);
+        }
+    } else if pending.is_changed() {
+        let shown: String = pending.initials.chars().chain(pending.letter).collect();
+        for mut text in &mut q_text {
+            text.0 = format!("New high score! Initials: {:_<3}", shown);
+        }
+    }
+}
+
+// `step` along LETTERS, wrapping round. a fresh slot starts at A going
+// up and 9 going down
+fn cycle_letter(letter: Option<char>, step: i32) -> char {
+    let len = LETTERS.len() as i32;
+    let i = match letter.and_then(|c| LETTERS.iter().position(|l| *l as char == c)) {
+        Some(i) => (i as i32 + step).rem_euclid(len),
+        None if step > 0 => 0,
+        None => len - 1,
+    };
+    LETTERS[i as usize] as char
+}
+
+fn save_pending_score(
+    mut commands: Commands,
+    pending: Option<Res<PendingScore>>,
+    mut high_scores: ResMut<HighScores>,
+) {
+    if let Some(pending) = pending {
+        save_score(&pending, &mut high_scores);
+        commands.remove_resource::<PendingScore>();
+    }
+}
+
+fn save_score(pending: &PendingScore, high_scores: &mut HighScores) {
+    let initials = if pending.initials.is_empty() {
+        "---".to_string()
+    } else {
+        pending.initials.clone()
+    };
+
+    high_scores.insert(
+        pending.mode,
+        HighScore {
+            initials,
+            score: pending.score,
+            level: pending.level,
+            date: storage::today(),
+        },
+    );
+    storage::save(STORAGE_KEY, &*high_scores);
+}
+
+fn format_entry(mode: Mode, rank: usize, entry: &HighScore) -> String {
+    match mode {
+        Mode::Classic => format!(
+            "{:>2}. {:<3} {:>7} L{:<2} {}",
+            rank, entry.initials, entry.score, entry.level, entry.date
+        ),
+        Mode::Endless => format!(
+            "{:>2}. {:<3} {:02}:{:02} {}",
+            rank,
+            entry.initials,
+            entry.score / 60,
+            entry.score % 60,
+            entry.date
+        ),
+    }
+}
+
+fn setup_high_scores(
+    mut commands: Commands,
+    game_assets: Res<GameAssets>,
+    high_scores: Res<HighScores>,
+) {
+    commands.spawn((Camera2d, HighScoresNode, Name::from("High Scores Camera")));
+
+    commands
+        .spawn((
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                padding: UiRect::all(Val::Px(30.0)),
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::SpaceBetween,
+                flex_direction: FlexDirection::Column,
+                ..default()
+            },
+            HighScoresNode,
+            Name::from("High Scores"),
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new("High Scores"),
+                TextColor(Color::WHITE),
+                TextFont {
+                    font: game_assets.font.clone(),
+                    font_size: 40.0,
+                    ..default()
+                },
+            ));
+
+            parent
+                .spawn(Node {
+                    width: Val::Percent(100.0),
+                    justify_content: JustifyContent::SpaceEvenly,
+                    ..default()
+                })
+                .with_children(|parent| {
+                    for (mode, title) in [(Mode::Classic, "Classic"), (Mode::Endless, "Endless")] {
+                        let table = high_scores.table(mode);
+                        let rows = if table.is_empty() {
+                            "No scores yet".to_string()
+                        } else {
+                            table
+                                .iter()
+                                .enumerate()
+                                .map(|(i, entry)| format_entry(mode, i + 1, entry))
+                                .collect::<Vec<_>>()
+                                .join("\n")
+                        };
+
+                        parent
+                            .spawn(Node {
+                                flex_direction: FlexDirection::Column,
+                                row_gap: Val::Px(15.0),
+                                ..default()
+                            })
+                            .with_children(|parent| {
+                                parent.spawn((
+                                    Text::new(title),
+                                    TextColor(Color::srgb(0.58, 0.60, 0.69)),
+                                    TextFont {
+                                        font: game_assets.font.clone(),
+                                        font_size: 26.0,
+                                        ..default()
+                                    },
+                                ));
+                                parent.spawn((
+                                    Text::new(rows),
+                                    TextColor(Color::WHITE),
+                                    TextFont {
+                                        font: game_assets.font.clone(),
+                                        font_size: 14.0,
+                                        ..default()
+                                    },
+                                    TextLayout::new_with_linebreak(LineBreak::NoWrap),
+                                ));
+                            });
+                    }
+                });
+
+            parent
+                .spawn((
+                    Button,
+                    Node {
+                        width: Val::Px(225.0),
+                        height: Val::Px(70.0),
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        ..default()
+                    },
+                    BorderRadius::all(Val::Px(10.0)),
+                    BackgroundColor(Color::srgb(0.15, 0.15, 0.15)),
+                    GameButton {
+                        action: GameButtonAction::ReturnToMenu,
+                        idle_color: Color::srgb(0.15, 0.15, 0.15),
+                        hover_color: Color::srgb(0.25, 0.25, 0.25),
+                    },
+                ))
+                .with_children(|parent| {
+                    parent.spawn((
+                        Text::new("Menu"),
+                        TextColor(Color::srgb(0.9, 0.9, 0.9)),
+                        TextFont {
+                            font: game_assets.font.clone(),
+                            font_size: 30.0,
+                            ..default()
+                        },
+                    ));
+                });
+        });
+}
+
+fn back_to_menu(
+    kb: Res<ButtonInput<KeyCode>>,
+    q_gamepads: Query<&Gamepad>,
+    mut app_state: ResMut<NextState<AppState>>,
+) {
+    let back = q_gamepads.iter().any(|pad| pad.just_pressed(GamepadButton::East));
+    if kb.just_pressed(KeyCode::Escape) || back {
+        app_state.set(AppState::Menu);
+    }
+}
+
+fn despawn_high_scores(mut commands: Commands, query: Query<Entity, With<HighScoresNode>>) {
+    for ent in &query {
+        commands.entity(ent).despawn();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(initials: &str, score: usize) -> HighScore {
+        HighScore {
+            initials: initials.to_string(),
+            score,
+            level: 1,
+            date: "2026-01-01".to_string(),
+        }
+    }
+
+    fn initials(high_scores: &HighScores, mode: Mode) -> Vec<&str> {
+        high_scores
+            .table(mode)
+            .iter()
+            .map(|s| s.initials.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn insert_keeps_the_table_sorted() {
+        let mut high_scores = HighScores::default();
+        high_scores.insert(Mode::Classic, entry("BBB", 200));
+        high_scores.insert(Mode::Classic, entry("CCC", 100));
+        high_scores.insert(Mode::Classic, entry("AAA", 300));
+        assert_eq!(initials(&high_scores, Mode::Classic), ["AAA", "BBB", "CCC"]);
+        assert!(high_scores.table(Mode::Endless).is_empty());
+    }
+
+    #[test]
+    fn insert_puts_ties_after_the_first_to_get_there() {
+        let mut high_scores = HighScores::default();
+        high_scores.insert(Mode::Endless, entry("OLD", 100));
+        high_scores.insert(Mode::Endless, entry("NEW", 100));
+        assert_eq!(initials(&high_scores, Mode::Endless), ["OLD", "NEW"]);
+    }
+
+    #[test]
+    fn insert_drops_whatever_falls_off_the_end() {
+        let mut high_scores = HighScores::default();
+        for score in 1..=MAX_SCORES {
+            high_scores.insert(Mode::Classic, entry("---", score * 10));
+        }
+        high_scores.insert(Mode::Classic, entry("TOP", 1000));
+
+        let table = high_scores.table(Mode::Classic);
+        assert_eq!(table.len(), MAX_SCORES);
+        assert_eq!(table[0].initials, "TOP");
+        assert_eq!(table[MAX_SCORES - 1].score, 20);
+    }
+
+    #[test]
+    fn qualifies_until_the_table_is_full() {
+        let mut high_scores = HighScores::default();
+        assert!(!high_scores.qualifies(Mode::Classic, 0));
+        assert!(high_scores.qualifies(Mode::Classic, 1));
+
+        for _ in 0..MAX_SCORES {
+            high_scores.insert(Mode::Classic, entry("---", 50));
+        }
+        assert!(!high_scores.qualifies(Mode::Classic, 50));
+        assert!(high_scores.qualifies(Mode::Classic, 51));
+        assert!(high_scores.qualifies(Mode::Endless, 1));
+    }
+
+    #[test]
+    fn cycle_letter_wraps_round() {
+        assert_eq!(cycle_letter(None, 1), 'A');
+        assert_eq!(cycle_letter(None, -1), '9');
+        assert_eq!(cycle_letter(Some('A'), 1), 'B');
+        assert_eq!(cycle_letter(Some('A'), -1), '9');
+        assert_eq!(cycle_letter(Some('Z'), 1), '0');
+        assert_eq!(cycle_letter(Some('9'), 1), 'A');
+    }
+}
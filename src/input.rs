@@ -0,0 +1,164 @@
+use bevy::prelude::*;
+use std::f32::consts::FRAC_PI_4;
+
+use super::{
+    controls::{Action, Controls},
+    game::{player_input, Player},
+    pause::PauseState,
+};
+
+const DIRECTIONS: [(Action, Vec2); 4] = [
+    (Action::Up, Vec2::Y),
+    (Action::Left, Vec2::NEG_X),
+    (Action::Down, Vec2::NEG_Y),
+    (Action::Right, Vec2::X),
+];
+// how far the stick has to be pushed before it steers
+const STICK_DEADZONE: f32 = 0.5;
+
+// the player's controls, read every frame whatever state the game's in
+// so presses during the countdown are still there when play starts
+#[derive(Resource, Default)]
+pub struct PlayerInput {
+    // directions held on the keyboard, most recently pressed last
+    held: Vec<Action>,
+    // where the ship should be heading, kept after the keys are let go
+    heading: Option<Vec2>,
+    heading_changed: bool,
+    // where the d-pad or stick is pointing
+    pad: Option<Vec2>,
+    fire_held: bool,
+    // a tap of fire that hasn't turned into a shot yet
+    fire_buffered: bool,
+    // seconds until the next shot is allowed
+    cooldown: f32,
+}
+
+impl PlayerInput {
+    // per axis the last key pressed wins, so holding W then
+    // tapping S turns around and letting go of S goes back up
+    fn direction(&self) -> Option<Vec2> {
+        let latest = |axis: fn(Vec2) -> f32| {
+            self.held
+                .iter()
+                .rev()
+                .filter_map(|action| DIRECTIONS.iter().find(|(a, _)| a == action))
+                .map(|(_, dir)| axis(*dir))
+                .find(|v| *v != 0.0)
+                .unwrap_or(0.0)
+        };
+
+        let dir = Vec2::new(latest(|d| d.x), latest(|d| d.y));
+        (dir != Vec2::ZERO).then_some(dir.normalize())
+    }
+
+    // the new heading, once per change
+    pub fn take_heading(&mut self) -> Option<Vec2> {
+        if !std::mem::take(&mut self.heading_changed) {
+            return None;
+        }
+        self.heading
+    }
+
+    // true when a shot should go out this frame, at most one every
+    // `interval` seconds and only while there's `room` for more lasers
+    pub fn fire(&mut self, delta: f32, interval: f32, room: bool) -> bool {
+        self.cooldown = (self.cooldown - delta).max(0.0);
+        if !room || self.cooldown > 0.0 || !(self.fire_held || self.fire_buffered) {
+            return false;
+        }
+
+        self.cooldown = interval;
+        self.fire_buffered = false;
+        true
+    }
+
+    // for a freshly spawned ship, only what's held right now carries over
+    fn reset(&mut self) {
+        self.heading = self.direction().or(self.pad);
+        self.heading_changed = self.heading.is_some();
+        self.fire_buffered = false;
+    }
+}
+
+pub struct PlayerInputPlugin;
+impl Plugin for PlayerInputPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PlayerInput>()
+            .add_systems(Update, read_controls.before(player_input))
+            // whatever was pressed on the pause menu stays there
+            .add_systems(OnExit(PauseState::Paused), forget_presses);
+    }
+}
+
+// d-pad first, then the stick, snapped to the 8 ways the ship can fly
+fn pad_direction(gamepad: &Gamepad, controls: &Controls) -> Option<Vec2> {
+    let dpad: Vec2 = DIRECTIONS
+        .iter()
+        .filter(|(action, _)| gamepad.pressed(controls.get(*action).button))
+        .map(|(_, dir)| *dir)
+        .sum();
+    let raw = if dpad != Vec2::ZERO { dpad } else { gamepad.left_stick() };
+    if raw.length() < STICK_DEADZONE {
+        return None;
+    }
+
+    let angle = (raw.to_angle() / FRAC_PI_4).round() * FRAC_PI_4;
+    Some(Vec2::from_angle(angle))
+}
+
+// every connected pad works, so plugging one in mid-game just works
+fn read_controls(
+    kb: Res<ButtonInput<KeyCode>>,
+    controls: Res<Controls>,
+    mut input: ResMut<PlayerInput>,
+    q_gamepads: Query<&Gamepad>,
+    q_new_player: Query<(), Added<Player>>,
+) {
+    if !q_new_player.is_empty() {
+        input.reset();
+    }
+
+    let mut changed = false;
+    for (action, _) in DIRECTIONS {
+        let key = controls.get(action).key;
+        if kb.just_released(key) {
+            input.held.retain(|a| *a != action);
+            changed = true;
+        }
+        if kb.just_pressed(key) {
+            input.held.retain(|a| *a != action);
+            input.held.push(action);
+            changed = true;
+        }
+    }
+
+    // letting go of everything keeps the ship going the same way
+    if changed {
+        if let Some(dir) = input.direction() {
+            if input.heading != Some(dir) {
+                input.heading = Some(dir);
+                input.heading_changed = true;
+            }
+        }
+    }
+
+    // same for the pad, whichever moved last steers
+    let pad = q_gamepads.iter().find_map(|pad| pad_direction(pad, &controls));
+    if pad != input.pad {
+        input.pad = pad;
+        if pad.is_some() && input.heading != pad {
+            input.heading = pad;
+            input.heading_changed = true;
+        }
+    }
+
+    input.fire_held = controls.pressed(Action::Fire, &kb, q_gamepads.iter());
+    if controls.just_pressed(Action::Fire, &kb, q_gamepads.iter()) {
+        input.fire_buffered = true;
+    }
+}
+
+fn forget_presses(mut input: ResMut<PlayerInput>) {
+    input.reset();
+}
@@ -1,355 +1,276 @@
+use bevy::{
+    asset::{io::Reader, AssetLoader, LoadContext},
+    ecs::system::SystemParam,
+    prelude::*,
+};
+use ron::ser::PrettyConfig;
+use serde::{Deserialize, Serialize};
 
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Star {
     pub x: f32,
     pub y: f32,
     pub vert: bool,
 }
 
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Rock {
     pub x: f32,
     pub y: f32,
     pub mine: bool,
 }
 
-pub const MAX_LEVEL: usize = 3;
+// a leader and its escorts, flying in some time after the level starts
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Formation {
+    // seconds after the countdown
+    pub time: f32,
+    // 4 to 6
+    pub escorts: usize,
+}
 
-//
-// LEVEL 1
-//
-pub struct Level1 {
-    pub stars: [Star; 3],
-    pub rocks: [Rock; 64],
+#[derive(Asset, TypePath, Clone, Serialize, Deserialize)]
+pub struct Level {
+    pub stars: Vec<Star>,
+    pub rocks: Vec<Rock>,
     pub start_i: usize,
     pub max_i: usize,
     pub start_p: usize,
     pub max_p: usize,
     pub time_limit: usize,
+    // in the order they show up
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub formations: Vec<Formation>,
 }
 
-pub const LEVEL_1: Level1 = Level1 {
-    stars: [
-        Star { x: -750.0, y: 350.0, vert: false },
-        Star { x: 300.0, y: -700.0, vert: false },
-        Star { x: 500.0, y: 700.0, vert: true },
-    ],
-    rocks: [
-        Rock { x: -2400.0, y: 600.0, mine: false },
-        Rock { x: -2300.0, y: 2200.0, mine: true },
-        Rock { x: -2100.0, y: -1750.0, mine: false },
-        Rock { x: -1950.0, y: 1600.0, mine: false },
-        Rock { x: -1950.0, y: -600.0, mine: true },
-        Rock { x: -1900.0, y: -1350.0, mine: false },
-        Rock { x: -1750.0, y: -2100.0, mine: false },
-        Rock { x: -1700.0, y: 850.0, mine: true },
-        Rock { x: -1600.0, y: -200.0, mine: false },
-        Rock { x: -1400.0, y: -1800.0, mine: true },
-        Rock { x: -1350.0, y: 0.0, mine: true },
-        Rock { x: -1300.0, y: 1250.0, mine: false },
-        Rock { x: -1250.0, y: -1200.0, mine: true },
-        Rock { x: -1100.0, y: 1850.0, mine: true },
-        Rock { x: -1100.0, y: -600.0, mine: true },
-        Rock { x: -1050.0, y: 950.0, mine: false },
-        Rock { x: -1050.0, y: 700.0, mine: false },
-        Rock { x: -950.0, y: 1100.0, mine: false },
-        Rock { x: -950.0, y: -1550.0, mine: false },
-        Rock { x: -900.0, y: 850.0, mine: false },
-        Rock { x: -850.0, y: 1000.0, mine: false },
-        Rock { x: -800.0, y: 1150.0, mine: false },
-        Rock { x: -800.0, y: -950.0, mine: true },
-        Rock { x: -750.0, y: 900.0, mine: false },
-        Rock { x: -650.0, y: 200.0, mine: false },
-        Rock { x: -600.0, y: 1100.0, mine: false },
-        Rock { x: -600.0, y: -400.0, mine: false },
-        Rock { x: -550.0, y: 1550.0, mine: false },
-        Rock { x: -550.0, y: 900.0, mine: false },
-        Rock { x: -400.0, y: -2100.0, mine: false },
-        Rock { x: -350.0, y: 1300.0, mine: false },
-        Rock { x: -250.0, y: 2150.0, mine: false },
-        Rock { x: -250.0, y: -1450.0, mine: true },
-        Rock { x: -200.0, y: 850.0, mine: false },
-        Rock { x: 100.0, y: 500.0, mine: false },
-        Rock { x: 100.0, y: -900.0, mine: true },
-        Rock { x: 300.0, y: -500.0, mine: false },
-        Rock { x: 350.0, y: 1900.0, mine: true },
-        Rock { x: 400.0, y: -2100.0, mine: true },
-        Rock { x: 450.0, y: 1700.0, mine: false },
-        Rock { x: 450.0, y: 1100.0, mine: true },
-        Rock { x: 450.0, y: -1600.0, mine: false },
-        Rock { x: 550.0, y: 2250.0, mine: false },
-        Rock { x: 650.0, y: 150.0, mine: false },
-        Rock { x: 900.0, y: -1800.0, mine: false },
-        Rock { x: 1000.0, y: 1400.0, mine: true },
-        Rock { x: 1000.0, y: 750.0, mine: false },
-        Rock { x: 1000.0, y: -150.0, mine: true },
-        Rock { x: 1050.0, y: -750.0, mine: false },
-        Rock { x: 1150.0, y: 2000.0, mine: true },
-        Rock { x: 1250.0, y: 400.0, mine: false },
-        Rock { x: 1350.0, y: -1500.0, mine: true },
-        Rock { x: 1500.0, y: 900.0, mine: true },
-        Rock { x: 1500.0, y: -2150.0, mine: false },
-        Rock { x: 1550.0, y: 1450.0, mine: false },
-        Rock { x: 1600.0, y: 2100.0, mine: false },
-        Rock { x: 1650.0, y: 50.0, mine: true },
-        Rock { x: 1650.0, y: -1700.0, mine: false },
-        Rock { x: 1800.0, y: -1050.0, mine: true },
-        Rock { x: 2000.0, y: 550.0, mine: false },
-        Rock { x: 2050.0, y: 1100.0, mine: false },
-        Rock { x: 2050.0, y: -500.0, mine: false },
-        Rock { x: 2100.0, y: 1850.0, mine: true },
-        Rock { x: 2200.0, y: -2000.0, mine: true },
-    ],
-    start_i: 3,
-    max_i: 6,
-    start_p: 3,
-    max_p: 6,
-    time_limit: 30,
-};
+impl Default for Level {
+    fn default() -> Self {
+        Level {
+            stars: Vec::new(),
+            rocks: Vec::new(),
+            start_i: 3,
+            max_i: 6,
+            start_p: 3,
+            max_p: 6,
+            time_limit: 30,
+            formations: Vec::new(),
+        }
+    }
+}
 
+// every level found in assets/levels, in order
+#[derive(Resource, Default)]
+pub struct Levels(pub Vec<Handle<Level>>);
 
-//
-// LEVEL 2
-//
-pub struct Level2 {
-    pub stars: [Star; 6],
-    pub rocks: [Rock; 100],
-    pub start_i: usize,
-    pub max_i: usize,
-    pub start_p: usize,
-    pub max_p: usize,
-    pub time_limit: usize,
+// a level from the editor, played on its own instead of the level list
+#[derive(Resource)]
+pub struct Playtest(pub Level);
+
+// looks up loaded levels by number, starting at 1
+#[derive(SystemParam)]
+pub struct GameLevels<'w> {
+    levels: Res<'w, Levels>,
+    assets: Res<'w, Assets<Level>>,
+    playtest: Option<Res<'w, Playtest>>,
 }
 
-pub const LEVEL_2: Level2 = Level2 {
-    stars: [
-        Star { x: -1300.0, y: 0.0, vert: false },
-        Star { x: -700.0, y: 1000.0, vert: true },
-        Star { x: -700.0, y: -1000.0, vert: true },
-        Star { x: 700.0, y: 1000.0, vert: false },
-        Star { x: 700.0, y: -1000.0, vert: false },
-        Star { x: 1300.0, y: 0.0, vert: true },
-    ],
-    rocks: [
-        Rock { x: -2400.0, y: -700.0, mine: false },
-        Rock { x: -2400.0, y: -1700.0, mine: false },
-        Rock { x: -2400.0, y: -2350.0, mine: false },
-        Rock { x: -2350.0, y: 1300.0, mine: false },
-        Rock { x: -2350.0, y: 450.0, mine: false },
-        Rock { x: -2300.0, y: 2100.0, mine: true },
-        Rock { x: -2250.0, y: -400.0, mine: false },
-        Rock { x: -2200.0, y: 950.0, mine: true },
-        Rock { x: -2150.0, y: -1900.0, mine: false },
-        Rock { x: -2100.0, y: 0.0, mine: false },
-        Rock { x: -2050.0, y: 2400.0, mine: false },
-        Rock { x: -2050.0, y: 1950.0, mine: false },
-        Rock { x: -2050.0, y: -750.0, mine: true },
-        Rock { x: -1900.0, y: 1500.0, mine: true },
-        Rock { x: -1900.0, y: 400.0, mine: true },
-        Rock { x: -1850.0, y: 1150.0, mine: false },
-        Rock { x: -1800.0, y: -1500.0, mine: true },
-        Rock { x: -1700.0, y: -1200.0, mine: false },
-        Rock { x: -1650.0, y: 2300.0, mine: true },
-        Rock { x: -1600.0, y: 1850.0, mine: false },
-        Rock { x: -1600.0, y: -50.0, mine: false },
-        Rock { x: -1600.0, y: -100.0, mine: false },
-        Rock { x: -1500.0, y: 800.0, mine: true },
-        Rock { x: -1500.0, y: -1550.0, mine: true },
-        Rock { x: -1500.0, y: -2200.0, mine: false },
-        Rock { x: -1350.0, y: -600.0, mine: false },
-        Rock { x: -1300.0, y: -950.0, mine: true },
-        Rock { x: -1250.0, y: 1300.0, mine: false },
-        Rock { x: -1100.0, y: 500.0, mine: false },
-        Rock { x: -1050.0, y: 1800.0, mine: true },
-        Rock { x: -1050.0, y: -550.0, mine: true },
-        Rock { x: -1050.0, y: -2000.0, mine: false },
-        Rock { x: -1000.0, y: 800.0, mine: true },
-        Rock { x: -950.0, y: -1400.0, mine: false },
-        Rock { x: -850.0, y: 2400.0, mine: false },
-        Rock { x: -850.0, y: 1800.0, mine: false },
-        Rock { x: -800.0, y: -1450.0, mine: false },
-        Rock { x: -700.0, y: 0.0, mine: false },
-        Rock { x: -700.0, y: -150.0, mine: false },
-        Rock { x: -650.0, y: 200.0, mine: true },
-        Rock { x: -600.0, y: -400.0, mine: true },
-        Rock { x: -550.0, y: 2400.0, mine: true },
-        Rock { x: -450.0, y: -850.0, mine: true },
-        Rock { x: -400.0, y: -2200.0, mine: false },
-        Rock { x: -350.0, y: 1250.0, mine: false },
-        Rock { x: -350.0, y: -2200.0, mine: false },
-        Rock { x: -250.0, y: 2050.0, mine: true },
-        Rock { x: -250.0, y: -1300.0, mine: true },
-        Rock { x: -200.0, y: 600.0, mine: true },
-        Rock { x: -150.0, y: 1450.0, mine: true },
-        Rock { x: -150.0, y: -650.0, mine: true },
-        Rock { x: -100.0, y: -1600.0, mine: true },
-        Rock { x: 0.0, y: -2050.0, mine: false },
-        Rock { x: 50.0, y: 350.0, mine: false },
-        Rock { x: 100.0, y: 2000.0, mine: false },
-        Rock { x: 150.0, y: 1100.0, mine: true },
-        Rock { x: 200.0, y: -250.0, mine: true },
-        Rock { x: 200.0, y: -2250.0, mine: false },
-        Rock { x: 350.0, y: 2450.0, mine: true },
-        Rock { x: 350.0, y: -1550.0, mine: false },
-        Rock { x: 450.0, y: 2150.0, mine: true },
-        Rock { x: 450.0, y: 1700.0, mine: false },
-        Rock { x: 600.0, y: 1050.0, mine: true },
-        Rock { x: 600.0, y: -2150.0, mine: true },
-        Rock { x: 650.0, y: -400.0, mine: false },
-        Rock { x: 700.0, y: 500.0, mine: true },
-        Rock { x: 850.0, y: 2200.0, mine: false },
-        Rock { x: 850.0, y: -750.0, mine: true },
-        Rock { x: 900.0, y: -1150.0, mine: false },
-        Rock { x: 950.0, y: 1750.0, mine: true },
-        Rock { x: 1000.0, y: -1700.0, mine: true },
-        Rock { x: 1050.0, y: 1050.0, mine: false },
-        Rock { x: 1050.0, y: -2100.0, mine: false },
-        Rock { x: 1250.0, y: 600.0, mine: false },
-        Rock { x: 1300.0, y: 2350.0, mine: true },
-        Rock { x: 1300.0, y: -1600.0, mine: false },
-        Rock { x: 1350.0, y: 1300.0, mine: false },
-        Rock { x: 1450.0, y: 1700.0, mine: false },
-        Rock { x: 1500.0, y: 1100.0, mine: true },
-        Rock { x: 1500.0, y: -1050.0, mine: true },
-        Rock { x: 1500.0, y: -2100.0, mine: false },
-        Rock { x: 1550.0, y: 0.0, mine: true },
-        Rock { x: 1550.0, y: -750.0, mine: false },
-        Rock { x: 1600.0, y: 2150.0, mine: false },
-        Rock { x: 1650.0, y: 600.0, mine: true },
-        Rock { x: 1650.0, y: -1650.0, mine: false },
-        Rock { x: 1800.0, y: 950.0, mine: false },
-        Rock { x: 1850.0, y: 1600.0, mine: true },
-        Rock { x: 1900.0, y: -450.0, mine: false },
-        Rock { x: 1900.0, y: -1250.0, mine: false },
-        Rock { x: 2000.0, y: 200.0, mine: false },
-        Rock { x: 2050.0, y: -700.0, mine: true },
-        Rock { x: 2050.0, y: -1900.0, mine: true },
-        Rock { x: 2100.0, y: 2350.0, mine: false },
-        Rock { x: 2200.0, y: 1200.0, mine: false },
-        Rock { x: 2250.0, y: 2150.0, mine: false },
-        Rock { x: 2250.0, y: -1050.0, mine: true },
-        Rock { x: 2300.0, y: 500.0, mine: true },
-        Rock { x: 2350.0, y: -2350.0, mine: false },
-        Rock { x: 2400.0, y: -150.0, mine: false },
-    ],
-    start_i: 3,
-    max_i: 6,
-    start_p: 3,
-    max_p: 6,
-    time_limit: 30,
-};
+impl GameLevels<'_> {
+    pub fn get(&self, level: usize) -> Option<&Level> {
+        if let Some(playtest) = &self.playtest {
+            return (level == 1).then_some(&playtest.0);
+        }
+
+        self.levels
+            .0
+            .get(level.checked_sub(1)?)
+            .and_then(|handle| self.assets.get(handle))
+    }
+
+    pub fn len(&self) -> usize {
+        if self.playtest.is_some() {
+            return 1;
+        }
+
+        self.levels.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+// levels are numbered from 1 with no gaps, the first
+// missing file marks the end of the list
+pub fn level_path(level: usize) -> String {
+    format!("levels/level_{}.level.ron", level)
+}
+
+#[derive(Default)]
+pub struct LevelLoader;
 
+impl AssetLoader for LevelLoader {
+    type Asset = Level;
+    type Settings = ();
+    type Error = BevyError;
 
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Level, BevyError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        Ok(level_from_ron(&bytes)?)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["level.ron"]
+    }
+}
+
+pub fn level_from_ron(bytes: &[u8]) -> Result<Level, ron::error::SpannedError> {
+    ron::de::from_bytes(bytes)
+}
+
+pub fn level_to_ron(level: &Level) -> Result<String, ron::Error> {
+    ron::ser::to_string_pretty(level, PrettyConfig::new().depth_limit(2)).map(|s| s + "\n")
+}
+
+// builds a level from a palette-coded map named like
+// level_<n>_<start_i>_<max_i>_<start_p>_<max_p>_<time_limit>.png
 //
-// LEVEL 3
+//   #00ff00  horizontal star
+//   #ffff00  vertical star
+//   #ff0000  asteroid
+//   #0000ff  mine
 //
-pub struct Level3 {
-    pub stars: [Star; 4],
-    pub rocks: [Rock; 83],
-    pub start_i: usize,
-    pub max_i: usize,
-    pub start_p: usize,
-    pub max_p: usize,
-    pub time_limit: usize,
+// and returns it along with its level number
+pub fn level_from_png(file_name: &str, bytes: &[u8]) -> Result<(usize, Level), BevyError> {
+    // one pixel of a level map covers this much of the arena
+    const MAP_TILE_SIZE: f32 = 50.0;
+    const HALF_ARENA_SIZE: f32 = 2500.0;
+
+    let numbers = file_name
+        .strip_suffix(".png")
+        .and_then(|name| name.strip_prefix("level_"))
+        .ok_or_else(|| format!("{} is not named level_<n>_..._<time_limit>.png", file_name))?
+        .split('_')
+        .map(|n| n.parse::<usize>())
+        .collect::<Result<Vec<usize>, _>>()?;
+
+    let [n, start_i, max_i, start_p, max_p, time_limit] = numbers[..] else {
+        return Err(format!("{} should have six numbers in its name", file_name).into());
+    };
+
+    let img = image::load_from_memory(bytes)?.to_rgb8();
+    let mut stars = Vec::new();
+    let mut rocks = Vec::new();
+    for x in 0..img.width() {
+        for y in 0..img.height() {
+            let xx = (x as f32 * MAP_TILE_SIZE) - HALF_ARENA_SIZE;
+            let yy = HALF_ARENA_SIZE - (y as f32 * MAP_TILE_SIZE);
+            match img.get_pixel(x, y).0 {
+                [0x00, 0xff, 0x00] => stars.push(Star { x: xx, y: yy, vert: false }),
+                [0xff, 0xff, 0x00] => stars.push(Star { x: xx, y: yy, vert: true }),
+                [0xff, 0x00, 0x00] => rocks.push(Rock { x: xx, y: yy, mine: false }),
+                [0x00, 0x00, 0xff] => rocks.push(Rock { x: xx, y: yy, mine: true }),
+                _ => {}
+            }
+        }
+    }
+
+    Ok((
+        n,
+        Level {
+            stars,
+            rocks,
+            start_i,
+            max_i,
+            start_p,
+            max_p,
+            time_limit,
+            formations: Vec::new(),
+        },
+    ))
 }
 
-pub const LEVEL_3: Level3 = Level3 {
-    stars: [
-        Star { x: -1400.0, y: 200.0, vert: false },
-        Star { x: -500.0, y: 200.0, vert: false },
-        Star { x: 850.0, y: 400.0, vert: true },
-        Star { x: 850.0, y: -500.0, vert: true },
-    ],
-    rocks: [
-        Rock { x: -2450.0, y: -2050.0, mine: true },
-        Rock { x: -2300.0, y: -50.0, mine: true },
-        Rock { x: -2250.0, y: 1450.0, mine: true },
-        Rock { x: -2100.0, y: 2200.0, mine: false },
-        Rock { x: -2100.0, y: -1150.0, mine: false },
-        Rock { x: -2050.0, y: -100.0, mine: false },
-        Rock { x: -2050.0, y: -1000.0, mine: true },
-        Rock { x: -2050.0, y: -1700.0, mine: true },
-        Rock { x: -1950.0, y: 450.0, mine: true },
-        Rock { x: -1850.0, y: 1550.0, mine: false },
-        Rock { x: -1750.0, y: 850.0, mine: false },
-        Rock { x: -1600.0, y: -400.0, mine: false },
-        Rock { x: -1550.0, y: 0.0, mine: false },
-        Rock { x: -1550.0, y: -1050.0, mine: false },
-        Rock { x: -1550.0, y: -2300.0, mine: true },
-        Rock { x: -1450.0, y: -1450.0, mine: true },
-        Rock { x: -1400.0, y: 800.0, mine: false },
-        Rock { x: -1350.0, y: 2100.0, mine: false },
-        Rock { x: -1250.0, y: 1600.0, mine: true },
-        Rock { x: -1150.0, y: -600.0, mine: true },
-        Rock { x: -1000.0, y: 1000.0, mine: true },
-        Rock { x: -950.0, y: 2200.0, mine: true },
-        Rock { x: -950.0, y: -2050.0, mine: true },
-        Rock { x: -900.0, y: -1150.0, mine: true },
-        Rock { x: -850.0, y: 1350.0, mine: true },
-        Rock { x: -800.0, y: 1950.0, mine: false },
-        Rock { x: -800.0, y: 400.0, mine: false },
-        Rock { x: -800.0, y: -250.0, mine: false },
-        Rock { x: -750.0, y: -1400.0, mine: false },
-        Rock { x: -750.0, y: -1700.0, mine: false },
-        Rock { x: -700.0, y: 1100.0, mine: true },
-        Rock { x: -650.0, y: 1500.0, mine: true },
-        Rock { x: -450.0, y: -350.0, mine: true },
-        Rock { x: -450.0, y: -900.0, mine: false },
-        Rock { x: -400.0, y: 2350.0, mine: false },
-        Rock { x: -250.0, y: 1900.0, mine: true },
-        Rock { x: -250.0, y: -1550.0, mine: true },
-        Rock { x: -200.0, y: -2200.0, mine: true },
-        Rock { x: -150.0, y: 1650.0, mine: true },
-        Rock { x: -150.0, y: -1400.0, mine: false },
-        Rock { x: -50.0, y: 900.0, mine: true },
-        Rock { x: 0.0, y: 1400.0, mine: false },
-        Rock { x: 0.0, y: -700.0, mine: true },
-        Rock { x: 100.0, y: -150.0, mine: false },
-        Rock { x: 150.0, y: 2150.0, mine: false },
-        Rock { x: 150.0, y: -1250.0, mine: true },
-        Rock { x: 200.0, y: 1850.0, mine: true },
-        Rock { x: 250.0, y: 150.0, mine: true },
-        Rock { x: 400.0, y: 1150.0, mine: true },
-        Rock { x: 400.0, y: 450.0, mine: false },
-        Rock { x: 400.0, y: -2100.0, mine: false },
-        Rock { x: 500.0, y: -1400.0, mine: false },
-        Rock { x: 550.0, y: 1650.0, mine: true },
-        Rock { x: 650.0, y: -500.0, mine: false },
-        Rock { x: 800.0, y: -1500.0, mine: false },
-        Rock { x: 850.0, y: 2100.0, mine: false },
-        Rock { x: 850.0, y: 1500.0, mine: false },
-        Rock { x: 850.0, y: 1200.0, mine: false },
-        Rock { x: 950.0, y: 800.0, mine: true },
-        Rock { x: 1050.0, y: -1100.0, mine: false },
-        Rock { x: 1150.0, y: 450.0, mine: true },
-        Rock { x: 1250.0, y: 1050.0, mine: false },
-        Rock { x: 1300.0, y: -100.0, mine: false },
-        Rock { x: 1350.0, y: -1900.0, mine: false },
-        Rock { x: 1400.0, y: 2250.0, mine: true },
-        Rock { x: 1450.0, y: 2000.0, mine: true },
-        Rock { x: 1550.0, y: -100.0, mine: false },
-        Rock { x: 1550.0, y: -2150.0, mine: false },
-        Rock { x: 1600.0, y: 1550.0, mine: false },
-        Rock { x: 1600.0, y: 450.0, mine: false },
-        Rock { x: 1700.0, y: -1700.0, mine: false },
-        Rock { x: 1850.0, y: 2150.0, mine: false },
-        Rock { x: 1850.0, y: 1000.0, mine: false },
-        Rock { x: 1900.0, y: 1900.0, mine: true },
-        Rock { x: 2000.0, y: 550.0, mine: true },
-        Rock { x: 2050.0, y: -500.0, mine: false },
-        Rock { x: 2050.0, y: -1050.0, mine: false },
-        Rock { x: 2050.0, y: -2100.0, mine: false },
-        Rock { x: 2100.0, y: 2350.0, mine: false },
-        Rock { x: 2100.0, y: 1100.0, mine: true },
-        Rock { x: 2200.0, y: 400.0, mine: false },
-        Rock { x: 2200.0, y: -1600.0, mine: false },
-        Rock { x: 2400.0, y: 1550.0, mine: true },
-    ],
-    start_i: 3,
-    max_i: 6,
-    start_p: 3,
-    max_p: 6,
-    time_limit: 30,
-};
+#[cfg(test)]
+mod tests {
+    use super::*;
 
+    #[test]
+    fn shipped_levels_load() {
+        let dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("assets");
+        let mut count = 0;
+        while let Ok(bytes) = std::fs::read(dir.join(level_path(count + 1))) {
+            let level = level_from_ron(&bytes);
+            assert!(level.is_ok(), "level {}: {:?}", count + 1, level.err());
+            count += 1;
+        }
+        assert!(count > 0);
+    }
 
+    #[test]
+    fn level_to_ron_round_trips() {
+        let level = Level {
+            stars: vec![Star {
+                x: 100.0,
+                y: -250.5,
+                vert: true,
+            }],
+            rocks: vec![
+                Rock {
+                    x: 0.0,
+                    y: 50.0,
+                    mine: false,
+                },
+                Rock {
+                    x: -1200.0,
+                    y: 3.25,
+                    mine: true,
+                },
+            ],
+            formations: vec![Formation {
+                time: 15.0,
+                escorts: 5,
+            }],
+            ..default()
+        };
+
+        let ron = level_to_ron(&level).unwrap();
+        let loaded = level_from_ron(ron.as_bytes()).unwrap();
+        assert_eq!(level_to_ron(&loaded).unwrap(), ron);
+        assert_eq!(loaded.stars[0].y, -250.5);
+        assert!(loaded.rocks[1].mine);
+        assert_eq!(loaded.formations[0].escorts, 5);
+    }
+
+    #[test]
+    fn formations_are_optional() {
+        let ron = level_to_ron(&Level::default()).unwrap();
+        assert!(!ron.contains("formations"));
+        let loaded = level_from_ron(ron.as_bytes()).unwrap();
+        assert!(loaded.formations.is_empty());
+    }
+
+    #[test]
+    fn shipped_levels_round_trip() {
+        let dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("assets");
+        for n in 1.. {
+            let Ok(bytes) = std::fs::read(dir.join(level_path(n))) else {
+                break;
+            };
+            let level = level_from_ron(&bytes).unwrap();
+            let ron = level_to_ron(&level).unwrap();
+            let loaded = level_from_ron(ron.as_bytes()).unwrap();
+            assert_eq!(level_to_ron(&loaded).unwrap(), ron);
+        }
+    }
+
+    #[test]
+    fn bad_level_is_an_error() {
+        assert!(level_from_ron(b"(stars: [], rocks: [").is_err());
+        assert!(level_from_ron(b"(stars: [], rocks: [])").is_err());
+    }
+}
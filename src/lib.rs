@@ -0,0 +1,2 @@
+// what the game and the tools in src/bin share
+pub mod levels;
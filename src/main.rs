@@ -6,14 +6,29 @@ use bevy_prototype_lyon::prelude::*;
 use bevy_rapier2d::prelude::*;
 // use bevy_inspector_egui::{bevy_egui::EguiPlugin, quick::WorldInspectorPlugin};
 
+mod announcer;
 mod classic;
+mod controls;
+mod director;
+mod editor;
 mod endless;
+mod field;
+mod focus;
+mod formation;
 mod game;
-mod levels;
+mod highscores;
+mod input;
 mod menu;
+mod music;
+mod pause;
+mod powerup;
+mod settings;
 mod setup;
+mod sfx;
+mod storage;
 
 pub use setup::{GameAssets, Atlas};
+use bosconian::levels;
 
 
 #[derive(Clone, Copy, Default, Eq, PartialEq, Debug, Hash, States)]
@@ -23,6 +38,10 @@ pub enum AppState {
     Menu,
     Classic,
     Endless,
+    Editor,
+    HighScores,
+    Controls,
+    Settings,
 }
 
 fn main() {
@@ -54,6 +73,18 @@ fn main() {
             menu::MenuPlugin,
             classic::ClassicPlugin,
             endless::EndlessPlugin,
+            editor::EditorPlugin,
+            highscores::HighScoresPlugin,
+            controls::ControlsPlugin,
+            pause::PausePlugin,
+            settings::SettingsPlugin,
+            input::PlayerInputPlugin,
+            focus::MenuFocusPlugin,
+        ))
+        .add_plugins((
+            sfx::SfxPlugin,
+            music::MusicPlugin,
+            announcer::AnnouncerPlugin,
         ))
         .init_state::<AppState>()
         .run();
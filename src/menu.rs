@@ -1,7 +1,7 @@
 use bevy::{app::AppExit, prelude::*};
 use webbrowser;
 
-use super::{AppState, GameAssets};
+use super::{levels::Playtest, AppState, GameAssets};
 
 const REPO_URL: &str = "https://github.com/dbusteed/bosconian";
 
@@ -19,6 +19,9 @@ enum MenuButtonAction {
     VisitRepo,
     Classic,
     Endless,
+    Editor,
+    HighScores,
+    Settings,
     Quit,
 }
 
@@ -36,6 +39,9 @@ fn setup_menu(
     game_assets: Res<GameAssets>,
     asset_server: Res<AssetServer>,
 ) {
+    // done playtesting, Classic goes back to the normal levels
+    commands.remove_resource::<Playtest>();
+
     commands.spawn((Camera2d, Menu, Name::from("Menu Camera")));
 
     // background
@@ -101,8 +107,8 @@ fn setup_menu(
                 .spawn((
                     Button,
                     Node {
-                        width: Val::Px(225.0),
-                        height: Val::Px(70.0),
+                        width: Val::Px(300.0),
+                        height: Val::Px(48.0),
                         justify_content: JustifyContent::Center,
                         align_items: AlignItems::Center,
                         ..default()
@@ -121,7 +127,7 @@ fn setup_menu(
                         TextColor(Color::srgb(0.0, 0.0, 0.0)),
                         TextFont {
                             font: game_assets.font.clone(),
-                            font_size: 26.0,
+                            font_size: 24.0,
                             ..default()
                         },
                     ));
@@ -131,8 +137,8 @@ fn setup_menu(
                 .spawn((
                     Button,
                     Node {
-                        width: Val::Px(225.0),
-                        height: Val::Px(70.0),
+                        width: Val::Px(300.0),
+                        height: Val::Px(48.0),
                         justify_content: JustifyContent::Center,
                         align_items: AlignItems::Center,
                         ..default()
@@ -151,7 +157,97 @@ fn setup_menu(
                         TextColor(Color::srgb(0.0, 0.0, 0.0)),
                         TextFont {
                             font: game_assets.font.clone(),
-                            font_size: 26.0,
+                            font_size: 24.0,
+                            ..default()
+                        },
+                    ));
+                });
+
+            parent
+                .spawn((
+                    Button,
+                    Node {
+                        width: Val::Px(300.0),
+                        height: Val::Px(48.0),
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        ..default()
+                    },
+                    BorderRadius::all(Val::Px(10.0)),
+                    BackgroundColor(Color::srgb(0.86, 0.88, 0.91)),
+                    MenuButton {
+                        action: MenuButtonAction::Editor,
+                        idle_color: Color::srgb(0.86, 0.88, 0.91),
+                        hover_color: Color::srgb(0.58, 0.60, 0.69),
+                    },
+                ))
+                .with_children(|parent| {
+                    parent.spawn((
+                        Text::new("Editor"),
+                        TextColor(Color::srgb(0.0, 0.0, 0.0)),
+                        TextFont {
+                            font: game_assets.font.clone(),
+                            font_size: 24.0,
+                            ..default()
+                        },
+                    ));
+                });
+
+            parent
+                .spawn((
+                    Button,
+                    Node {
+                        width: Val::Px(300.0),
+                        height: Val::Px(48.0),
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        ..default()
+                    },
+                    BorderRadius::all(Val::Px(10.0)),
+                    BackgroundColor(Color::srgb(0.86, 0.88, 0.91)),
+                    MenuButton {
+                        action: MenuButtonAction::HighScores,
+                        idle_color: Color::srgb(0.86, 0.88, 0.91),
+                        hover_color: Color::srgb(0.58, 0.60, 0.69),
+                    },
+                ))
+                .with_children(|parent| {
+                    parent.spawn((
+                        Text::new("High Scores"),
+                        TextColor(Color::srgb(0.0, 0.0, 0.0)),
+                        TextFont {
+                            font: game_assets.font.clone(),
+                            font_size: 24.0,
+                            ..default()
+                        },
+                    ));
+                });
+
+            parent
+                .spawn((
+                    Button,
+                    Node {
+                        width: Val::Px(300.0),
+                        height: Val::Px(48.0),
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        ..default()
+                    },
+                    BorderRadius::all(Val::Px(10.0)),
+                    BackgroundColor(Color::srgb(0.86, 0.88, 0.91)),
+                    MenuButton {
+                        action: MenuButtonAction::Settings,
+                        idle_color: Color::srgb(0.86, 0.88, 0.91),
+                        hover_color: Color::srgb(0.58, 0.60, 0.69),
+                    },
+                ))
+                .with_children(|parent| {
+                    parent.spawn((
+                        Text::new("Settings"),
+                        TextColor(Color::srgb(0.0, 0.0, 0.0)),
+                        TextFont {
+                            font: game_assets.font.clone(),
+                            font_size: 24.0,
                             ..default()
                         },
                     ));
@@ -163,8 +259,8 @@ fn setup_menu(
                     .spawn((
                         Button,
                         Node {
-                            width: Val::Px(225.0),
-                            height: Val::Px(70.0),
+                            width: Val::Px(300.0),
+                            height: Val::Px(48.0),
                             justify_content: JustifyContent::Center,
                             align_items: AlignItems::Center,
                             ..default()
@@ -183,7 +279,7 @@ fn setup_menu(
                             TextColor(Color::srgb(0.0, 0.0, 0.0)),
                             TextFont {
                                 font: game_assets.font.clone(),
-                                font_size: 26.0,
+                                font_size: 24.0,
                                 ..default()
                             },
                         ));
@@ -214,6 +310,9 @@ fn button_system(
                 match button.action {
                     MenuButtonAction::Classic => game_state.set(AppState::Classic),
                     MenuButtonAction::Endless => game_state.set(AppState::Endless),
+                    MenuButtonAction::Editor => game_state.set(AppState::Editor),
+                    MenuButtonAction::HighScores => game_state.set(AppState::HighScores),
+                    MenuButtonAction::Settings => game_state.set(AppState::Settings),
                     // .write returns the eventID, suppress with ;
                     MenuButtonAction::Quit => {
                         exit.write(AppExit::Success);
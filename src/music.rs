@@ -0,0 +1,139 @@
+use bevy::{
+    audio::{PlaybackMode, Volume},
+    prelude::*,
+    state::state::StateTransitionEvent,
+};
+
+use super::{pause::PauseState, settings::Settings, AppState, GameAssets};
+
+// seconds for one track to fade out while the next fades in
+const CROSSFADE_SECONDS: f32 = 1.5;
+// how loud the music stays behind the pause menu
+const PAUSE_DUCK: f32 = 0.3;
+const DUCK_SECONDS: f32 = 0.3;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Track {
+    Menu,
+    Classic,
+    Endless,
+    RedAlert,
+    GameOver,
+}
+
+impl Track {
+    fn handle(&self, game_assets: &GameAssets) -> Handle<AudioSource> {
+        match self {
+            Track::Menu => game_assets.menu_music.clone(),
+            Track::Classic => game_assets.classic_music.clone(),
+            Track::Endless => game_assets.endless_music.clone(),
+            Track::RedAlert => game_assets.red_alert_music.clone(),
+            Track::GameOver => game_assets.game_over_music.clone(),
+        }
+    }
+}
+
+// set by a mode to play something other than its usual track, cleared
+// whenever the app state changes
+#[derive(Resource, Default)]
+pub struct MusicCue(pub Option<Track>);
+
+// background music, fading towards full if it's the track that should
+// be playing and towards nothing (then gone) if it isn't
+#[derive(Component)]
+pub struct Music {
+    track: Track,
+    fade: f32,
+}
+
+#[derive(Resource)]
+struct Duck(f32);
+
+pub struct MusicPlugin;
+impl Plugin for MusicPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<MusicCue>()
+            .insert_resource(Duck(1.0))
+            .add_systems(Update, (clear_cue, play_music).chain());
+    }
+}
+
+fn clear_cue(
+    mut transitions: MessageReader<StateTransitionEvent<AppState>>,
+    mut cue: ResMut<MusicCue>,
+) {
+    if transitions.read().count() > 0 {
+        cue.0 = None;
+    }
+}
+
+fn wanted_track(app_state: &AppState, cue: &MusicCue) -> Option<Track> {
+    if cue.0.is_some() {
+        return cue.0;
+    }
+
+    match app_state {
+        AppState::Setup => None,
+        AppState::Classic => Some(Track::Classic),
+        AppState::Endless => Some(Track::Endless),
+        _ => Some(Track::Menu),
+    }
+}
+
+// real time, so the fades carry on while the game is paused
+fn play_music(
+    mut commands: Commands,
+    time: Res<Time<Real>>,
+    game_assets: Option<Res<GameAssets>>,
+    settings: Res<Settings>,
+    cue: Res<MusicCue>,
+    mut duck: ResMut<Duck>,
+    app_state: Res<State<AppState>>,
+    pause_state: Option<Res<State<PauseState>>>,
+    mut q_music: Query<(Entity, &mut Music, Option<&mut AudioSink>)>,
+) {
+    let Some(game_assets) = game_assets else {
+        return;
+    };
+
+    let wanted = wanted_track(app_state.get(), &cue);
+    let step = time.delta_secs() / CROSSFADE_SECONDS;
+
+    let paused = pause_state.is_some_and(|state| *state.get() == PauseState::Paused);
+    let duck_target = if paused { PAUSE_DUCK } else { 1.0 };
+    let duck_step = time.delta_secs() / DUCK_SECONDS;
+    duck.0 += (duck_target - duck.0).clamp(-duck_step, duck_step);
+
+    let mut playing = false;
+    for (ent, mut music, sink) in &mut q_music {
+        if Some(music.track) == wanted {
+            playing = true;
+            music.fade = (music.fade + step).min(1.0);
+        } else {
+            music.fade -= step;
+            if music.fade <= 0.0 {
+                commands.entity(ent).despawn();
+                continue;
+            }
+        }
+
+        if let Some(mut sink) = sink {
+            sink.set_volume(Volume::Linear(
+                settings.music_volume().to_linear() * music.fade * duck.0,
+            ));
+        }
+    }
+
+    if let (Some(track), false) = (wanted, playing) {
+        commands.spawn((
+            AudioPlayer::new(track.handle(&game_assets)),
+            PlaybackSettings {
+                mode: PlaybackMode::Loop,
+                volume: Volume::SILENT,
+                ..default()
+            },
+            Music { track, fade: 0.0 },
+            Name::from("Background Music"),
+        ));
+    }
+}
@@ -0,0 +1,172 @@
+use bevy::{prelude::*, window::WindowFocused};
+use bevy_rapier2d::prelude::*;
+
+use super::{
+    controls::{Action, Controls},
+    game::{button_system, GameButton, GameButtonAction},
+    settings::SettingsPanel,
+    AppState, GameAssets,
+};
+
+// only around during a game, each mode adds `toggle_pause` for the
+// states it can be paused in
+#[derive(SubStates, Clone, Copy, Default, Eq, PartialEq, Debug, Hash)]
+#[source(AppState = AppState::Classic | AppState::Endless)]
+pub enum PauseState {
+    #[default]
+    Running,
+    Paused,
+}
+
+// not a GameNode, so a restart leaves it (and the settings panel) for `resume`
+#[derive(Component)]
+struct PauseMenu;
+
+pub struct PausePlugin;
+impl Plugin for PausePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_sub_state::<PauseState>()
+            .add_systems(OnEnter(PauseState::Paused), pause)
+            .add_systems(OnExit(PauseState::Paused), resume)
+            .add_systems(
+                Update,
+                (button_system, hide_behind_settings)
+                    .chain()
+                    .run_if(in_state(PauseState::Paused)),
+            );
+    }
+}
+
+// the pause key or button flips it, losing focus only ever pauses
+pub fn toggle_pause(
+    kb: Res<ButtonInput<KeyCode>>,
+    controls: Res<Controls>,
+    q_gamepads: Query<&Gamepad>,
+    mut focus_events: MessageReader<WindowFocused>,
+    pause_state: Res<State<PauseState>>,
+    mut next_pause_state: ResMut<NextState<PauseState>>,
+) {
+    let lost_focus = focus_events.read().any(|event| !event.focused);
+    if controls.just_pressed(Action::Pause, &kb, q_gamepads.iter()) {
+        next_pause_state.set(match pause_state.get() {
+            PauseState::Running => PauseState::Paused,
+            PauseState::Paused => PauseState::Running,
+        });
+    } else if lost_focus {
+        next_pause_state.set(PauseState::Paused);
+    }
+}
+
+// stopping virtual time holds every timer, animation and the Endless
+// clock where they are, rapier gets switched off as well
+fn pause(
+    mut commands: Commands,
+    game_assets: Res<GameAssets>,
+    mut time: ResMut<Time<Virtual>>,
+    mut rapier_config: Query<&mut RapierConfiguration>,
+) {
+    time.pause();
+    for mut config in &mut rapier_config {
+        config.physics_pipeline_active = false;
+    }
+
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(20.0),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.6)),
+            GlobalZIndex(10),
+            PauseMenu,
+            Name::from("Pause Menu"),
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new("Paused"),
+                TextColor(Color::WHITE),
+                TextFont {
+                    font: game_assets.font.clone(),
+                    font_size: 40.0,
+                    ..default()
+                },
+            ));
+
+            for (action, label) in [
+                (GameButtonAction::Resume, "Resume"),
+                (GameButtonAction::Restart, "Restart"),
+                (GameButtonAction::Settings, "Settings"),
+                (GameButtonAction::ReturnToMenu, "Quit to Menu"),
+            ] {
+                parent
+                    .spawn((
+                        Button,
+                        Node {
+                            width: Val::Px(225.0),
+                            height: Val::Px(60.0),
+                            justify_content: JustifyContent::Center,
+                            align_items: AlignItems::Center,
+                            ..default()
+                        },
+                        BorderRadius::all(Val::Px(10.0)),
+                        BackgroundColor(Color::srgb(0.15, 0.15, 0.15)),
+                        GameButton {
+                            action,
+                            idle_color: Color::srgb(0.15, 0.15, 0.15),
+                            hover_color: Color::srgb(0.25, 0.25, 0.25),
+                        },
+                    ))
+                    .with_children(|parent| {
+                        parent.spawn((
+                            Text::new(label),
+                            TextColor(Color::srgb(0.9, 0.9, 0.9)),
+                            TextFont {
+                                font: game_assets.font.clone(),
+                                font_size: 26.0,
+                                ..default()
+                            },
+                        ));
+                    });
+            }
+        });
+}
+
+// hidden buttons can't be clicked or focused, so nothing under the
+// settings panel can be pressed through it
+fn hide_behind_settings(
+    q_settings: Query<(), With<SettingsPanel>>,
+    mut q_pause_menu: Query<&mut Visibility, With<PauseMenu>>,
+) {
+    let visibility = if q_settings.is_empty() {
+        Visibility::Inherited
+    } else {
+        Visibility::Hidden
+    };
+    for mut menu in &mut q_pause_menu {
+        menu.set_if_neq(visibility);
+    }
+}
+
+// also runs when the game is left while paused
+fn resume(
+    mut commands: Commands,
+    mut time: ResMut<Time<Virtual>>,
+    mut rapier_config: Query<&mut RapierConfiguration>,
+    q_pause_menu: Query<Entity, With<PauseMenu>>,
+    q_settings: Query<Entity, With<SettingsPanel>>,
+) {
+    time.unpause();
+    for mut config in &mut rapier_config {
+        config.physics_pipeline_active = true;
+    }
+
+    for ent in q_pause_menu.iter().chain(q_settings.iter()) {
+        commands.entity(ent).despawn();
+    }
+}
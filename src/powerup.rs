@@ -0,0 +1,323 @@
+use bevy::{camera::visibility::RenderLayers, prelude::*};
+use bevy_prototype_lyon::prelude::*;
+use bevy_rapier2d::prelude::*;
+use rand::Rng;
+
+use super::{
+    formation::FormationMember,
+    game::{
+        blink_pickup, Explodable, ExplodableType, ExplosionEvent, ExplosionSize, GameNode,
+        KillEvent, LevelNode, Player, PlayerProjectile, PICKUP_LIFETIME,
+    },
+    sfx::{Sfx, SfxEvent},
+    GameAssets,
+};
+
+const RAPID_FIRE_SECONDS: f32 = 10.0;
+// seconds between shots while fire is held
+const RAPID_FIRE_INTERVAL: f32 = 0.1;
+const SPREAD_SECONDS: f32 = 10.0;
+// each spread pickup adds two lasers, from 4 up to 8
+const MIN_SPREAD: usize = 4;
+const MAX_SPREAD: usize = 8;
+// after the shield breaks, a moment to get clear of whatever broke it
+const SHIELD_GRACE_SECONDS: f32 = 1.0;
+// the smart bomb clears about a screen around the player
+const SMART_BOMB_RANGE: f32 = 900.0;
+
+// in the same order as the frames of power_ups.png
+#[derive(Clone, Copy)]
+pub enum PowerUpKind {
+    RapidFire,
+    Spread,
+    Shield,
+    SmartBomb,
+}
+
+impl PowerUpKind {
+    const ALL: [PowerUpKind; 4] = [
+        PowerUpKind::RapidFire,
+        PowerUpKind::Spread,
+        PowerUpKind::Shield,
+        PowerUpKind::SmartBomb,
+    ];
+
+    // chance of a drop when the player shoots one of these down
+    fn drop_chance(explodable: &ExplodableType) -> f64 {
+        match explodable {
+            ExplodableType::Figher
+            | ExplodableType::IType
+            | ExplodableType::PType
+            | ExplodableType::EType => 0.06,
+            ExplodableType::StarCore => 0.5,
+            _ => 0.0,
+        }
+    }
+}
+
+// on the player, everything it's picked up that's still going
+#[derive(Component, Default)]
+pub struct PowerUps {
+    rapid_fire: Option<Timer>,
+    // lasers per shot, 0 for the usual front and back pair
+    spread: usize,
+    spread_timer: Option<Timer>,
+    shield: bool,
+    grace: Option<Timer>,
+}
+
+impl PowerUps {
+    pub fn spread(&self) -> usize {
+        self.spread
+    }
+
+    // seconds between shots while rapid fire lasts
+    pub fn fire_interval(&self) -> Option<f32> {
+        self.rapid_fire.as_ref().map(|_| RAPID_FIRE_INTERVAL)
+    }
+
+    // true if the shield (or the moment after it broke) saves the player
+    pub fn absorb_hit(&mut self) -> bool {
+        if self.grace.is_some() {
+            return true;
+        }
+
+        if self.shield {
+            self.shield = false;
+            self.grace = Some(Timer::from_seconds(SHIELD_GRACE_SECONDS, TimerMode::Once));
+            return true;
+        }
+
+        false
+    }
+
+    fn hud_text(&self) -> String {
+        let mut parts = Vec::new();
+        if let Some(timer) = &self.rapid_fire {
+            parts.push(format!("RAPID {}", timer.remaining_secs().ceil()));
+        }
+        if let Some(timer) = &self.spread_timer {
+            parts.push(format!("SPREAD x{} {}", self.spread, timer.remaining_secs().ceil()));
+        }
+        if self.shield {
+            parts.push("SHIELD".to_string());
+        }
+        parts.join("  ")
+    }
+}
+
+// a power-up floating where something was shot down
+#[derive(Component)]
+pub struct PowerUpPickup {
+    kind: PowerUpKind,
+    lifetime: Timer,
+}
+
+#[derive(Component)]
+pub struct PowerUpText;
+
+#[derive(Component)]
+pub struct ShieldRing;
+
+// the HUD line and the ring drawn around a shielded player
+pub fn spawn_power_up_hud(commands: &mut Commands, game_assets: &GameAssets) {
+    commands.spawn((
+        Text::new(""),
+        TextColor(Color::srgb(1.0, 0.85, 0.0)),
+        TextFont {
+            font: game_assets.font.clone(),
+            font_size: 20.0,
+            ..default()
+        },
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(10.0),
+            right: Val::Px(15.0),
+            ..default()
+        },
+        PowerUpText,
+        GameNode,
+    ));
+
+    commands.spawn((
+        ShapeBuilder::with(&shapes::Circle {
+            radius: 40f32,
+            center: Vec2::ZERO,
+        })
+        .stroke((Color::srgb(0.16, 0.78, 0.9), 3.0))
+        .build(),
+        Transform::from_xyz(0.0, 0.0, 2.0),
+        Visibility::Hidden,
+        ShieldRing,
+        RenderLayers::layer(0),
+        GameNode,
+    ));
+}
+
+pub fn maybe_drop_power_up(
+    commands: &mut Commands,
+    game_assets: &GameAssets,
+    explodable: &ExplodableType,
+    pos: Vec2,
+) {
+    let mut rng = rand::rng();
+    if !rng.random_bool(PowerUpKind::drop_chance(explodable)) {
+        return;
+    }
+
+    let kind = PowerUpKind::ALL[rng.random_range(0..PowerUpKind::ALL.len())];
+    commands.spawn((
+        Sprite {
+            image: game_assets.power_ups.texture.clone(),
+            texture_atlas: Some(TextureAtlas {
+                layout: game_assets.power_ups.layout.clone(),
+                index: kind as usize,
+            }),
+            ..default()
+        },
+        Transform::from_translation(pos.extend(5.0)),
+        RigidBody::Fixed,
+        Collider::ball(16.0),
+        Sensor,
+        CollisionGroups::new(
+            Group::from_bits_truncate(0b1000000000),
+            Group::from_bits_truncate(0b0000000001),
+        ),
+        PowerUpPickup {
+            kind,
+            lifetime: Timer::from_seconds(PICKUP_LIFETIME, TimerMode::Once),
+        },
+        RenderLayers::layer(0),
+        LevelNode,
+        GameNode,
+    ));
+}
+
+pub fn collect_power_ups(
+    mut commands: Commands,
+    time: Res<Time>,
+    rapier_context: ReadRapierContext,
+    mut q_pickups: Query<(Entity, &mut PowerUpPickup, &mut Visibility)>,
+    mut q_player: Query<(Entity, &GlobalTransform, &mut PowerUps), With<Player>>,
+    q_explodables: Query<(Entity, &GlobalTransform, &Explodable)>,
+    q_player_lasers: Query<(), With<PlayerProjectile>>,
+    q_formation_members: Query<&FormationMember>,
+    mut explosion_events: MessageWriter<ExplosionEvent>,
+    mut kill_events: MessageWriter<KillEvent>,
+    mut sfx_events: MessageWriter<SfxEvent>,
+) {
+    let context = rapier_context.single().unwrap();
+    let mut player = q_player.single_mut().ok();
+
+    for (ent, mut pickup, mut visibility) in q_pickups.iter_mut() {
+        pickup.lifetime.tick(time.delta());
+        if pickup.lifetime.is_finished() {
+            commands.entity(ent).despawn();
+            continue;
+        }
+
+        if let Some((p_ent, p_trans, power_ups)) = &mut player {
+            if context.intersection_pair(ent, *p_ent) == Some(true) {
+                commands.entity(ent).despawn();
+                sfx_events.write(SfxEvent::new(Sfx::Pickup));
+
+                match pickup.kind {
+                    PowerUpKind::RapidFire => {
+                        power_ups.rapid_fire =
+                            Some(Timer::from_seconds(RAPID_FIRE_SECONDS, TimerMode::Once));
+                    }
+                    PowerUpKind::Spread => {
+                        power_ups.spread = (power_ups.spread + 2).clamp(MIN_SPREAD, MAX_SPREAD);
+                        power_ups.spread_timer =
+                            Some(Timer::from_seconds(SPREAD_SECONDS, TimerMode::Once));
+                    }
+                    PowerUpKind::Shield => power_ups.shield = true,
+                    PowerUpKind::SmartBomb => {
+                        for (e_ent, e_trans, explo) in q_explodables.iter() {
+                            let enemy = match explo.0 {
+                                ExplodableType::Figher
+                                | ExplodableType::IType
+                                | ExplodableType::PType
+                                | ExplodableType::EType
+                                | ExplodableType::Missile => true,
+                                ExplodableType::Laser => !q_player_lasers.contains(e_ent),
+                                _ => false,
+                            };
+                            let distance = e_trans
+                                .translation()
+                                .truncate()
+                                .distance(p_trans.translation().truncate());
+                            if !enemy || distance > SMART_BOMB_RANGE {
+                                continue;
+                            }
+
+                            commands.entity(e_ent).despawn();
+                            if explo.0 != ExplodableType::Laser {
+                                explosion_events.write(ExplosionEvent {
+                                    size: ExplosionSize::Small,
+                                    x: e_trans.translation().x,
+                                    y: e_trans.translation().y,
+                                });
+                                // scored by the mode, same as if it had been shot
+                                kill_events.write(KillEvent {
+                                    explodable: explo.0,
+                                    pos: e_trans.translation().truncate(),
+                                    formation: q_formation_members.get(e_ent).ok().map(|m| m.0),
+                                });
+                            }
+                        }
+                    }
+                }
+                continue;
+            }
+        }
+
+        blink_pickup(&pickup.lifetime, &mut visibility);
+    }
+}
+
+// runs the timers down and keeps the HUD and shield ring up to date
+pub fn update_power_ups(
+    time: Res<Time>,
+    mut q_player: Query<(&GlobalTransform, &mut PowerUps), With<Player>>,
+    mut q_text: Query<&mut Text, With<PowerUpText>>,
+    mut q_ring: Query<(&mut Transform, &mut Visibility), With<ShieldRing>>,
+) {
+    let Ok((p_trans, mut power_ups)) = q_player.single_mut() else {
+        for mut text in &mut q_text {
+            text.0.clear();
+        }
+        for (_, mut visibility) in &mut q_ring {
+            *visibility = Visibility::Hidden;
+        }
+        return;
+    };
+
+    let power_ups = &mut *power_ups;
+    for timer in [&mut power_ups.rapid_fire, &mut power_ups.grace] {
+        if timer.as_mut().is_some_and(|t| t.tick(time.delta()).is_finished()) {
+            *timer = None;
+        }
+    }
+    if power_ups
+        .spread_timer
+        .as_mut()
+        .is_some_and(|t| t.tick(time.delta()).is_finished())
+    {
+        power_ups.spread_timer = None;
+        power_ups.spread = 0;
+    }
+
+    for mut text in &mut q_text {
+        text.0 = power_ups.hud_text();
+    }
+
+    for (mut trans, mut visibility) in &mut q_ring {
+        trans.translation = p_trans.translation().with_z(2.0);
+        *visibility = if power_ups.shield || power_ups.grace.is_some() {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+    }
+}
@@ -0,0 +1,559 @@
+use bevy::{
+    audio::Volume,
+    camera::{visibility::RenderLayers, ClearColorConfig, Viewport},
+    prelude::*,
+    ui::{FocusPolicy, RelativeCursorPosition},
+    window::{MonitorSelection, PresentMode, PrimaryWindow, WindowMode},
+};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    game::{MinimapCamera, MINIMAP_SIZE},
+    storage, AppState, GameAssets,
+};
+
+const STORAGE_KEY: &str = "settings";
+const IDLE_COLOR: Color = Color::srgb(0.15, 0.15, 0.15);
+const HOVER_COLOR: Color = Color::srgb(0.25, 0.25, 0.25);
+// the size the game is laid out for, before scaling
+const WINDOW_WIDTH: f32 = 1000.0;
+const WINDOW_HEIGHT: f32 = 750.0;
+const SCALES: [f32; 4] = [1.0, 1.25, 1.5, 2.0];
+const VOLUME_STEP: f32 = 0.1;
+
+#[derive(Resource, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    // 0 to 1, music and sound effects are scaled by master
+    pub master_volume: f32,
+    pub music_volume: f32,
+    pub sfx_volume: f32,
+    pub fullscreen: bool,
+    pub vsync: bool,
+    pub scale: f32,
+    pub minimap: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            master_volume: 1.0,
+            music_volume: 0.5,
+            sfx_volume: 1.0,
+            fullscreen: false,
+            vsync: true,
+            scale: 1.0,
+            minimap: true,
+        }
+    }
+}
+
+impl Settings {
+    pub fn music_volume(&self) -> Volume {
+        Volume::Linear(self.master_volume * self.music_volume)
+    }
+
+    pub fn sfx_volume(&self) -> Volume {
+        Volume::Linear(self.master_volume * self.sfx_volume)
+    }
+
+    fn volume(&self, setting: Setting) -> Option<f32> {
+        match setting {
+            Setting::Master => Some(self.master_volume),
+            Setting::Music => Some(self.music_volume),
+            Setting::Sfx => Some(self.sfx_volume),
+            _ => None,
+        }
+    }
+
+    fn volume_mut(&mut self, setting: Setting) -> Option<&mut f32> {
+        match setting {
+            Setting::Master => Some(&mut self.master_volume),
+            Setting::Music => Some(&mut self.music_volume),
+            Setting::Sfx => Some(&mut self.sfx_volume),
+            _ => None,
+        }
+    }
+
+    fn cycle(&mut self, setting: Setting) {
+        match setting {
+            Setting::Display => self.fullscreen = !self.fullscreen,
+            Setting::Vsync => self.vsync = !self.vsync,
+            Setting::Scale => {
+                let i = SCALES.iter().position(|s| *s == self.scale).unwrap_or(0);
+                self.scale = SCALES[(i + 1) % SCALES.len()];
+            }
+            Setting::Minimap => self.minimap = !self.minimap,
+            _ => {}
+        }
+    }
+
+    fn value_text(&self, setting: Setting) -> String {
+        let on_off = |on: bool| if on { "On" } else { "Off" }.to_string();
+        match setting {
+            Setting::Master => format!("{:.0}%", self.master_volume * 100.0),
+            Setting::Music => format!("{:.0}%", self.music_volume * 100.0),
+            Setting::Sfx => format!("{:.0}%", self.sfx_volume * 100.0),
+            Setting::Display if self.fullscreen => "Fullscreen".to_string(),
+            Setting::Display => "Windowed".to_string(),
+            Setting::Vsync => on_off(self.vsync),
+            Setting::Scale => format!("{}x", self.scale),
+            Setting::Minimap => on_off(self.minimap),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Setting {
+    Master,
+    Music,
+    Sfx,
+    Display,
+    Vsync,
+    Scale,
+    Minimap,
+}
+
+impl Setting {
+    fn label(&self) -> &'static str {
+        match self {
+            Setting::Master => "Master",
+            Setting::Music => "Music",
+            Setting::Sfx => "Sound FX",
+            Setting::Display => "Display",
+            Setting::Vsync => "Vsync",
+            Setting::Scale => "Window Scale",
+            Setting::Minimap => "Minimap",
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum SettingsButtonAction {
+    Lower(Setting),
+    Raise(Setting),
+    Cycle(Setting),
+    Controls,
+    Back,
+}
+
+// the whole screen, also opened over the pause menu
+#[derive(Component)]
+pub struct SettingsPanel;
+
+#[derive(Component)]
+struct SettingsButton(SettingsButtonAction);
+
+#[derive(Component)]
+struct SliderBar(Setting);
+
+#[derive(Component)]
+struct SliderFill(Setting);
+
+#[derive(Component)]
+struct ValueText(Setting);
+
+pub struct SettingsPlugin;
+impl Plugin for SettingsPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(storage::load::<Settings>(STORAGE_KEY).unwrap_or_default())
+            .add_systems(OnEnter(AppState::Settings), setup_settings)
+            .add_systems(
+                Update,
+                (
+                    button_system,
+                    drag_sliders,
+                    update_settings_panel,
+                    save_settings,
+                )
+                    .chain()
+                    .run_if(any_with_component::<SettingsPanel>),
+            )
+            .add_systems(Update, apply_settings.run_if(resource_changed::<Settings>))
+            // after the game has had its say about the minimap
+            .add_systems(PostUpdate, fit_minimap)
+            .add_systems(OnExit(AppState::Settings), despawn_settings);
+    }
+}
+
+fn setup_settings(mut commands: Commands, game_assets: Res<GameAssets>) {
+    commands.spawn((Camera2d, SettingsPanel, Name::from("Settings Camera")));
+    spawn_settings_panel(&mut commands, &game_assets, true);
+}
+
+// `from_menu` adds the way into Controls, which would end a game
+pub fn spawn_settings_panel(commands: &mut Commands, game_assets: &GameAssets, from_menu: bool) {
+    let font = |size: f32| TextFont {
+        font: game_assets.font.clone(),
+        font_size: size,
+        ..default()
+    };
+
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                padding: UiRect::all(Val::Px(30.0)),
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::SpaceBetween,
+                flex_direction: FlexDirection::Column,
+                ..default()
+            },
+            BackgroundColor(Color::BLACK),
+            GlobalZIndex(20),
+            // the pause menu is still under it
+            FocusPolicy::Block,
+            SettingsPanel,
+            Name::from("Settings"),
+        ))
+        .with_children(|parent| {
+            parent.spawn((Text::new("Settings"), TextColor(Color::WHITE), font(40.0)));
+
+            parent
+                .spawn(Node {
+                    flex_direction: FlexDirection::Column,
+                    row_gap: Val::Px(12.0),
+                    ..default()
+                })
+                .with_children(|parent| {
+                    for setting in [
+                        Setting::Master,
+                        Setting::Music,
+                        Setting::Sfx,
+                        Setting::Display,
+                        Setting::Vsync,
+                        Setting::Scale,
+                        Setting::Minimap,
+                    ] {
+                        parent
+                            .spawn(Node {
+                                column_gap: Val::Px(12.0),
+                                align_items: AlignItems::Center,
+                                ..default()
+                            })
+                            .with_children(|parent| {
+                                parent
+                                    .spawn(Node {
+                                        width: Val::Px(220.0),
+                                        ..default()
+                                    })
+                                    .with_children(|parent| {
+                                        parent.spawn((
+                                            Text::new(setting.label()),
+                                            TextColor(Color::WHITE),
+                                            font(18.0),
+                                        ));
+                                    });
+
+                                let is_volume = matches!(
+                                    setting,
+                                    Setting::Master | Setting::Music | Setting::Sfx
+                                );
+                                if is_volume {
+                                    spawn_button(
+                                        parent,
+                                        game_assets,
+                                        SettingsButtonAction::Lower(setting),
+                                        "-",
+                                        44.0,
+                                    );
+
+                                    // click or drag along it to set the volume
+                                    parent
+                                        .spawn((
+                                            Button,
+                                            Node {
+                                                width: Val::Px(200.0),
+                                                height: Val::Px(16.0),
+                                                ..default()
+                                            },
+                                            BackgroundColor(IDLE_COLOR),
+                                            RelativeCursorPosition::default(),
+                                            SliderBar(setting),
+                                        ))
+                                        .with_children(|parent| {
+                                            parent.spawn((
+                                                Node {
+                                                    width: Val::Percent(0.0),
+                                                    height: Val::Percent(100.0),
+                                                    ..default()
+                                                },
+                                                BackgroundColor(Color::srgb(0.86, 0.88, 0.91)),
+                                                SliderFill(setting),
+                                            ));
+                                        });
+
+                                    spawn_button(
+                                        parent,
+                                        game_assets,
+                                        SettingsButtonAction::Raise(setting),
+                                        "+",
+                                        44.0,
+                                    );
+
+                                    parent.spawn((
+                                        Text::new(""),
+                                        TextColor(Color::srgb(0.58, 0.60, 0.69)),
+                                        font(18.0),
+                                        ValueText(setting),
+                                    ));
+                                } else {
+                                    spawn_button(
+                                        parent,
+                                        game_assets,
+                                        SettingsButtonAction::Cycle(setting),
+                                        "",
+                                        200.0,
+                                    );
+                                }
+                            });
+                    }
+                });
+
+            parent
+                .spawn(Node {
+                    column_gap: Val::Px(30.0),
+                    ..default()
+                })
+                .with_children(|parent| {
+                    if from_menu {
+                        spawn_button(
+                            parent,
+                            game_assets,
+                            SettingsButtonAction::Controls,
+                            "Controls",
+                            200.0,
+                        );
+                    }
+                    spawn_button(
+                        parent,
+                        game_assets,
+                        SettingsButtonAction::Back,
+                        "Back",
+                        200.0,
+                    );
+                });
+        });
+}
+
+fn spawn_button(
+    parent: &mut ChildSpawnerCommands,
+    game_assets: &GameAssets,
+    action: SettingsButtonAction,
+    label: &str,
+    width: f32,
+) {
+    parent
+        .spawn((
+            Button,
+            Node {
+                width: Val::Px(width),
+                height: Val::Px(44.0),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            BorderRadius::all(Val::Px(10.0)),
+            BackgroundColor(IDLE_COLOR),
+            SettingsButton(action),
+        ))
+        .with_children(|parent| {
+            let mut text = parent.spawn((
+                Text::new(label),
+                TextColor(Color::srgb(0.9, 0.9, 0.9)),
+                TextFont {
+                    font: game_assets.font.clone(),
+                    font_size: 18.0,
+                    ..default()
+                },
+            ));
+            // cycling buttons show the current value
+            if let SettingsButtonAction::Cycle(setting) = action {
+                text.insert(ValueText(setting));
+            }
+        });
+}
+
+fn button_system(
+    mut commands: Commands,
+    mut interaction_query: Query<
+        (&Interaction, &SettingsButton, &mut BackgroundColor),
+        Changed<Interaction>,
+    >,
+    mut settings: ResMut<Settings>,
+    state: Res<State<AppState>>,
+    mut app_state: ResMut<NextState<AppState>>,
+    q_panel: Query<Entity, (With<SettingsPanel>, With<Node>)>,
+) {
+    for (interaction, button, mut color) in &mut interaction_query {
+        *color = match *interaction {
+            Interaction::Pressed | Interaction::Hovered => HOVER_COLOR.into(),
+            Interaction::None => IDLE_COLOR.into(),
+        };
+
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        match button.0 {
+            SettingsButtonAction::Lower(setting) | SettingsButtonAction::Raise(setting) => {
+                let step = match button.0 {
+                    SettingsButtonAction::Lower(_) => -VOLUME_STEP,
+                    _ => VOLUME_STEP,
+                };
+                if let Some(volume) = settings.volume_mut(setting) {
+                    // round so ten steps always gets back to exactly 0 or 1
+                    *volume = ((*volume + step).clamp(0.0, 1.0) * 100.0).round() / 100.0;
+                }
+            }
+            SettingsButtonAction::Cycle(setting) => settings.cycle(setting),
+            SettingsButtonAction::Controls => app_state.set(AppState::Controls),
+            // the screen from the main menu, the panel over the pause menu
+            SettingsButtonAction::Back if *state.get() == AppState::Settings => {
+                app_state.set(AppState::Menu)
+            }
+            SettingsButtonAction::Back => {
+                for ent in &q_panel {
+                    commands.entity(ent).despawn();
+                }
+            }
+        }
+    }
+}
+
+fn drag_sliders(
+    mut settings: ResMut<Settings>,
+    q_sliders: Query<(&Interaction, &RelativeCursorPosition, &SliderBar)>,
+) {
+    for (interaction, cursor, slider) in &q_sliders {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        let Some(pos) = cursor.normalized else {
+            continue;
+        };
+
+        // normalized runs from -0.5 to 0.5 across the bar
+        let value = ((pos.x + 0.5).clamp(0.0, 1.0) * 20.0).round() / 20.0;
+        if settings
+            .volume(slider.0)
+            .is_some_and(|volume| volume != value)
+        {
+            if let Some(volume) = settings.volume_mut(slider.0) {
+                *volume = value;
+            }
+        }
+    }
+}
+
+fn update_settings_panel(
+    settings: Res<Settings>,
+    mut q_text: Query<(&mut Text, &ValueText)>,
+    mut q_fill: Query<(&mut Node, &SliderFill)>,
+    q_new: Query<(), Added<SettingsPanel>>,
+) {
+    if !settings.is_changed() && q_new.is_empty() {
+        return;
+    }
+
+    for (mut text, ValueText(setting)) in &mut q_text {
+        text.0 = settings.value_text(*setting);
+    }
+
+    for (mut node, SliderFill(setting)) in &mut q_fill {
+        if let Some(volume) = settings.volume(*setting) {
+            node.width = Val::Percent(volume * 100.0);
+        }
+    }
+}
+
+fn save_settings(settings: Res<Settings>) {
+    if settings.is_changed() {
+        storage::save(STORAGE_KEY, &*settings);
+    }
+}
+
+// live, and once at startup for whatever was saved
+fn apply_settings(
+    settings: Res<Settings>,
+    mut q_window: Query<&mut Window, With<PrimaryWindow>>,
+    // what the window was last given, so a volume change doesn't snap a
+    // resized window back
+    mut applied: Local<Option<(bool, bool, f32)>>,
+) {
+    if let Ok(mut window) = q_window.single_mut() {
+        let display = (settings.fullscreen, settings.vsync, settings.scale);
+        if *applied == Some(display) {
+            return;
+        }
+        *applied = Some(display);
+
+        window.mode = if settings.fullscreen {
+            WindowMode::BorderlessFullscreen(MonitorSelection::Current)
+        } else {
+            WindowMode::Windowed
+        };
+        window.present_mode = if settings.vsync {
+            PresentMode::AutoVsync
+        } else {
+            PresentMode::AutoNoVsync
+        };
+
+        // the game keeps its 1000x750 layout, just drawn bigger. this
+        // replaces the OS scale, same as the 1.0 the window starts with
+        window
+            .resolution
+            .set_scale_factor_override(Some(settings.scale));
+        if !settings.fullscreen {
+            window.resolution.set(WINDOW_WIDTH, WINDOW_HEIGHT);
+        }
+    }
+}
+
+// keeps the minimap in the bottom right corner whatever the window
+// size, and draws nothing there when it's turned off
+fn fit_minimap(
+    settings: Res<Settings>,
+    q_window: Query<&Window, With<PrimaryWindow>>,
+    mut q_minimap: Query<(&mut Camera, &mut RenderLayers), With<MinimapCamera>>,
+) {
+    let Ok(window) = q_window.single() else {
+        return;
+    };
+
+    let size = (MINIMAP_SIZE * window.scale_factor()) as u32;
+    let viewport = Viewport {
+        physical_position: window.physical_size().saturating_sub(UVec2::splat(size)),
+        physical_size: UVec2::splat(size),
+        ..default()
+    };
+    let (layers, clear_color) = if settings.minimap {
+        (RenderLayers::layer(1), ClearColorConfig::Default)
+    } else {
+        (RenderLayers::none(), ClearColorConfig::None)
+    };
+
+    for (mut camera, mut render_layers) in &mut q_minimap {
+        if camera
+            .viewport
+            .as_ref()
+            .map(|v| (v.physical_position, v.physical_size))
+            != Some((viewport.physical_position, viewport.physical_size))
+        {
+            camera.viewport = Some(viewport.clone());
+        }
+        let cleared = !matches!(camera.clear_color, ClearColorConfig::None);
+        if cleared != settings.minimap {
+            camera.clear_color = clear_color;
+        }
+        render_layers.set_if_neq(layers.clone());
+    }
+}
+
+fn despawn_settings(mut commands: Commands, query: Query<Entity, With<SettingsPanel>>) {
+    for ent in &query {
+        commands.entity(ent).despawn();
+    }
+}
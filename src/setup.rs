@@ -1,9 +1,13 @@
 use bevy::{
+    asset::{io::AssetReaderError, AssetLoadError, LoadState},
     prelude::*,
-    audio::{PlaybackMode, PlaybackSettings, Volume}
 };
 
-use super::AppState;
+use super::{
+    director::{Difficulty, DifficultyLoader},
+    levels::{self, Level, LevelLoader, Levels},
+    AppState,
+};
 
 #[derive(Asset, TypePath, Clone)]
 pub struct Atlas {
@@ -25,13 +29,30 @@ pub struct GameAssets {
 
     // audio
     pub laser_sound: Handle<AudioSource>,
+    pub star_node_laser_sound: Handle<AudioSource>,
+    pub extra_life_sound: Handle<AudioSource>,
+    pub pickup_sound: Handle<AudioSource>,
+    pub explosion_sound: Handle<AudioSource>,
+    pub big_explosion_sound: Handle<AudioSource>,
+    pub player_death_sound: Handle<AudioSource>,
+    pub star_destroyed_sound: Handle<AudioSource>,
+    pub red_alert_sound: Handle<AudioSource>,
+    pub countdown_sound: Handle<AudioSource>,
+    pub menu_music: Handle<AudioSource>,
+    pub classic_music: Handle<AudioSource>,
+    pub endless_music: Handle<AudioSource>,
+    pub red_alert_music: Handle<AudioSource>,
+    pub game_over_music: Handle<AudioSource>,
+    pub blast_off_voice: Handle<AudioSource>,
+    pub spy_ship_sighted_voice: Handle<AudioSource>,
+    pub condition_red_voice: Handle<AudioSource>,
+    pub battle_stations_voice: Handle<AudioSource>,
 
     // game
     pub player: Atlas,
     pub i_type: Handle<Image>,
     pub p_type: Handle<Image>,
-    pub v_laser: Handle<Image>,
-    pub h_laser: Handle<Image>,
+    pub laser: Handle<Image>,
     pub explosion: Atlas,
     pub big_explosion: Atlas,
     pub star_node_laser: Atlas,
@@ -51,12 +72,30 @@ pub struct GameAssets {
     pub star_node_h4: Atlas,
     pub star_node_h5: Atlas,
     pub star_node_h6: Atlas,
+    pub star_hatch_v: Atlas,
+    pub star_hatch_h: Atlas,
+    pub power_ups: Atlas,
+    pub missile: Handle<Image>,
+    pub time_bonus: Handle<Image>,
+
+    // data
+    pub difficulty: Handle<Difficulty>,
 }
 
+// the next level file we're waiting on
+#[derive(Resource)]
+struct LevelProbe(Handle<Level>);
+
 pub struct SetupPlugin;
 impl Plugin for SetupPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Startup, setup);
+        app.init_asset::<Level>()
+            .init_asset_loader::<LevelLoader>()
+            .init_resource::<Levels>()
+            .init_asset::<Difficulty>()
+            .init_asset_loader::<DifficultyLoader>()
+            .add_systems(Startup, setup)
+            .add_systems(Update, load_levels.run_if(in_state(AppState::Setup)));
     }
 }
 
@@ -64,34 +103,42 @@ fn setup(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
     mut layouts: ResMut<Assets<TextureAtlasLayout>>,
-    mut app_state: ResMut<NextState<AppState>>
 ) {
-    commands.spawn((
-        AudioPlayer::new(asset_server.load("sounds/Test.ogg")),
-        PlaybackSettings {
-            mode: PlaybackMode::Loop,
-            volume: Volume::Linear(0.0),
-            ..default()
-        },
-        Name::from("Background Music"),
-    ));
-
     let game_assets = GameAssets {
         life: asset_server.load("player_single.png"),
         font: asset_server.load("fonts/emulogic.ttf"),
         menu_background: asset_server.load("menu_background_2.png"),
         background: asset_server.load("background.png"),
         laser_sound: asset_server.load("sounds/laser5.ogg"),
+        star_node_laser_sound: asset_server.load("sounds/star_node_laser.wav"),
+        extra_life_sound: asset_server.load("sounds/extra_life.wav"),
+        pickup_sound: asset_server.load("sounds/pickup.wav"),
+        explosion_sound: asset_server.load("sounds/explosion.wav"),
+        big_explosion_sound: asset_server.load("sounds/big_explosion.wav"),
+        player_death_sound: asset_server.load("sounds/player_death.wav"),
+        star_destroyed_sound: asset_server.load("sounds/star_destroyed.wav"),
+        red_alert_sound: asset_server.load("sounds/red_alert.wav"),
+        countdown_sound: asset_server.load("sounds/countdown.wav"),
+        menu_music: asset_server.load("music/menu.wav"),
+        classic_music: asset_server.load("music/classic.wav"),
+        endless_music: asset_server.load("music/endless.wav"),
+        red_alert_music: asset_server.load("music/red_alert.wav"),
+        game_over_music: asset_server.load("music/game_over.wav"),
+        blast_off_voice: asset_server.load("voice/blast_off.wav"),
+        spy_ship_sighted_voice: asset_server.load("voice/spy_ship_sighted.wav"),
+        condition_red_voice: asset_server.load("voice/condition_red.wav"),
+        battle_stations_voice: asset_server.load("voice/battle_stations.wav"),
         game_over: asset_server.load("game_over.png"),
         you_won: asset_server.load("you_won.png"),
         i_type: asset_server.load("i_type.png"),
         p_type: asset_server.load("p_type.png"),
-        h_laser: asset_server.load("h_laser.png"),
-        v_laser: asset_server.load("v_laser.png"),
+        laser: asset_server.load("laser.png"),
         v_star: asset_server.load("v_star.png"),
         h_star: asset_server.load("h_star.png"),
         mine: asset_server.load("mine.png"),
         asteroid: asset_server.load("asteroid.png"),
+        missile: asset_server.load("missile.png"),
+        time_bonus: asset_server.load("time_bonus.png"),
         player: Atlas {
             texture: asset_server.load("player.png"),
             layout: layouts.add(TextureAtlasLayout::from_grid(
@@ -272,9 +319,77 @@ fn setup(
                 None,
             )),
         },
+        star_hatch_v: Atlas {
+            texture: asset_server.load("star_hatch_v.png"),
+            layout: layouts.add(TextureAtlasLayout::from_grid(
+                UVec2::new(32, 64),
+                4,
+                1,
+                None,
+                None,
+            )),
+        },
+        star_hatch_h: Atlas {
+            texture: asset_server.load("star_hatch_h.png"),
+            layout: layouts.add(TextureAtlasLayout::from_grid(
+                UVec2::new(64, 32),
+                4,
+                1,
+                None,
+                None,
+            )),
+        },
+        power_ups: Atlas {
+            texture: asset_server.load("power_ups.png"),
+            layout: layouts.add(TextureAtlasLayout::from_grid(
+                UVec2::new(32, 32),
+                4,
+                1,
+                None,
+                None,
+            )),
+        },
+        difficulty: asset_server.load("endless.difficulty.ron"),
     };
 
     commands.insert_resource(game_assets);
 
-    app_state.set(AppState::Menu);
+    commands.insert_resource(LevelProbe(asset_server.load(levels::level_path(1))));
+}
+
+fn load_levels(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut probe: ResMut<LevelProbe>,
+    mut levels: ResMut<Levels>,
+    mut app_state: ResMut<NextState<AppState>>,
+) {
+    //
+    // load level_1, level_2, ... until one is missing,
+    // so new levels only need a file in assets/levels
+    //
+
+    match asset_server.load_state(&probe.0) {
+        LoadState::Loaded => {
+            levels.0.push(probe.0.clone());
+            probe.0 = asset_server.load(levels::level_path(levels.0.len() + 1));
+        }
+        // the level after the last one, or a broken file. that one cuts
+        // the campaign short there, so it's worth saying why
+        LoadState::Failed(err) => {
+            if !matches!(
+                *err,
+                AssetLoadError::AssetReaderError(AssetReaderError::NotFound(_))
+            ) {
+                error!(
+                    "couldn't load {}, stopping at level {}: {err}",
+                    levels::level_path(levels.0.len() + 1),
+                    levels.0.len()
+                );
+            }
+            commands.remove_resource::<LevelProbe>();
+            app_state.set(AppState::Menu);
+        }
+        _ => {}
+    }
 }
@@ -0,0 +1,285 @@
+use bevy::{
+    audio::{PlaybackMode, SpatialScale, Volume},
+    prelude::*,
+};
+use rand::Rng;
+
+use super::{
+    game::{ExplosionEvent, ExplosionSize, GameCamera, PlayerDeathEvent},
+    settings::Settings,
+    GameAssets,
+};
+
+// sounds playing at once, anything past this is dropped or cuts off
+// something less important
+const MAX_VOICES: usize = 12;
+// anything on screen plays at full volume, the game camera's 1.25 scale
+// shows 625 pixels either side. further out it falls off with the square
+// of the distance
+const FULL_VOLUME_DISTANCE: f32 = 500.0 * 1.25;
+// about a sixteenth of full volume, not worth a voice past here
+const MAX_HEARING_DISTANCE: f32 = 4.0 * FULL_VOLUME_DISTANCE;
+// z is left out, the camera sits far above everything
+const SPATIAL_SCALE: SpatialScale = SpatialScale(Vec3::new(
+    1.0 / FULL_VOLUME_DISTANCE,
+    1.0 / FULL_VOLUME_DISTANCE,
+    0.0,
+));
+// how far apart the listener's ears are, closer than this to straight
+// ahead or behind is only partly panned
+const EAR_GAP: f32 = 400.0;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Sfx {
+    Laser,
+    StarNodeLaser,
+    SmallExplosion,
+    BigExplosion,
+    PlayerDeath,
+    StarDestroyed,
+    RedAlert,
+    Countdown,
+    ExtraLife,
+    Pickup,
+}
+
+impl Sfx {
+    fn handle(&self, game_assets: &GameAssets) -> Handle<AudioSource> {
+        match self {
+            Sfx::Laser => game_assets.laser_sound.clone(),
+            Sfx::StarNodeLaser => game_assets.star_node_laser_sound.clone(),
+            Sfx::SmallExplosion => game_assets.explosion_sound.clone(),
+            Sfx::BigExplosion => game_assets.big_explosion_sound.clone(),
+            Sfx::PlayerDeath => game_assets.player_death_sound.clone(),
+            Sfx::StarDestroyed => game_assets.star_destroyed_sound.clone(),
+            Sfx::RedAlert => game_assets.red_alert_sound.clone(),
+            Sfx::Countdown => game_assets.countdown_sound.clone(),
+            Sfx::ExtraLife => game_assets.extra_life_sound.clone(),
+            Sfx::Pickup => game_assets.pickup_sound.clone(),
+        }
+    }
+
+    // mixed against each other, the sfx volume setting goes on top
+    fn volume(&self) -> f32 {
+        match self {
+            Sfx::Laser => 0.25,
+            Sfx::StarNodeLaser => 0.4,
+            Sfx::SmallExplosion => 0.5,
+            Sfx::Pickup => 0.6,
+            _ => 1.0,
+        }
+    }
+
+    // how far the pitch can wander either way, so repeats don't drone
+    fn pitch_variance(&self) -> f32 {
+        match self {
+            Sfx::Laser | Sfx::StarNodeLaser | Sfx::SmallExplosion => 0.1,
+            Sfx::BigExplosion | Sfx::StarDestroyed => 0.05,
+            _ => 0.0,
+        }
+    }
+
+    // higher plays first and can cut off lower
+    fn priority(&self) -> u8 {
+        match self {
+            Sfx::Laser | Sfx::StarNodeLaser => 0,
+            Sfx::SmallExplosion => 1,
+            Sfx::Pickup | Sfx::BigExplosion => 2,
+            Sfx::StarDestroyed | Sfx::Countdown => 3,
+            Sfx::ExtraLife | Sfx::RedAlert | Sfx::PlayerDeath => 4,
+        }
+    }
+
+    // the common ones can't take over every voice
+    fn max_voices(&self) -> usize {
+        match self {
+            Sfx::Laser | Sfx::SmallExplosion => 4,
+            Sfx::StarNodeLaser => 3,
+            _ => MAX_VOICES,
+        }
+    }
+}
+
+// with a position it's heard from the game camera, further away is
+// quieter and off to one side is panned that way
+#[derive(Message)]
+pub struct SfxEvent {
+    sfx: Sfx,
+    pos: Option<Vec2>,
+}
+
+impl SfxEvent {
+    pub fn new(sfx: Sfx) -> Self {
+        SfxEvent { sfx, pos: None }
+    }
+
+    pub fn at(sfx: Sfx, pos: Vec2) -> Self {
+        SfxEvent {
+            sfx,
+            pos: Some(pos),
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct SoundEffect {
+    sfx: Sfx,
+    started: f32,
+}
+
+pub struct SfxPlugin;
+impl Plugin for SfxPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_message::<SfxEvent>()
+            .add_message::<ExplosionEvent>()
+            .add_message::<PlayerDeathEvent>()
+            .add_systems(Update, (add_listener, play_sfx).chain())
+            .add_systems(Update, set_sfx_volume.run_if(resource_changed::<Settings>));
+    }
+}
+
+fn add_listener(mut commands: Commands, q_camera: Query<Entity, Added<GameCamera>>) {
+    for ent in &q_camera {
+        commands.entity(ent).insert(SpatialListener::new(EAR_GAP));
+    }
+}
+
+fn play_sfx(
+    mut commands: Commands,
+    time: Res<Time<Real>>,
+    game_assets: Option<Res<GameAssets>>,
+    settings: Res<Settings>,
+    mut sfx_events: MessageReader<SfxEvent>,
+    mut explosion_events: MessageReader<ExplosionEvent>,
+    mut death_events: MessageReader<PlayerDeathEvent>,
+    q_voices: Query<(Entity, &SoundEffect)>,
+    q_listener: Query<&GlobalTransform, With<SpatialListener>>,
+) {
+    let mut queue: Vec<(Sfx, Option<Vec2>)> = sfx_events
+        .read()
+        .map(|event| (event.sfx, event.pos))
+        .collect();
+    queue.extend(explosion_events.read().map(|event| {
+        let sfx = match event.size {
+            ExplosionSize::Small => Sfx::SmallExplosion,
+            ExplosionSize::Big => Sfx::BigExplosion,
+        };
+        (sfx, Some(Vec2::new(event.x, event.y)))
+    }));
+    queue.extend(death_events.read().map(|_| (Sfx::PlayerDeath, None)));
+
+    // nothing to play them with until setup is done
+    let Some(game_assets) = game_assets else {
+        return;
+    };
+
+    // out of earshot. without a listener there's nothing to place them
+    // against, so they play flat
+    let listener = q_listener
+        .single()
+        .ok()
+        .map(|trans| trans.translation().truncate());
+    queue.retain_mut(|(_, pos)| match (listener, *pos) {
+        (Some(listener), Some(p)) => p.distance(listener) <= MAX_HEARING_DISTANCE,
+        (None, Some(_)) => {
+            *pos = None;
+            true
+        }
+        _ => true,
+    });
+
+    // the same sound twice in a frame is just louder, the closest one
+    // is kept
+    queue.sort_by(|(a, a_pos), (b, b_pos)| {
+        let distance = |pos: &Option<Vec2>| match (listener, pos) {
+            (Some(listener), Some(pos)) => pos.distance(listener),
+            _ => 0.0,
+        };
+        b.priority()
+            .cmp(&a.priority())
+            .then((*a as u8).cmp(&(*b as u8)))
+            .then(distance(a_pos).total_cmp(&distance(b_pos)))
+    });
+    queue.dedup_by_key(|(sfx, _)| *sfx);
+
+    let mut voices: Vec<(Entity, Sfx, f32)> = q_voices
+        .iter()
+        .map(|(ent, voice)| (ent, voice.sfx, voice.started))
+        .collect();
+
+    let mut rng = rand::rng();
+    for (sfx, pos) in queue {
+        if voices.iter().filter(|(_, s, _)| *s == sfx).count() >= sfx.max_voices() {
+            continue;
+        }
+
+        if voices.len() >= MAX_VOICES {
+            // cut off the oldest of the least important
+            let Some(i) = voices
+                .iter()
+                .enumerate()
+                .filter(|(_, (_, s, _))| s.priority() < sfx.priority())
+                .min_by(|(_, a), (_, b)| {
+                    a.1.priority()
+                        .cmp(&b.1.priority())
+                        .then(a.2.total_cmp(&b.2))
+                })
+                .map(|(i, _)| i)
+            else {
+                continue;
+            };
+            let (ent, _, _) = voices.swap_remove(i);
+            commands.entity(ent).despawn();
+        }
+
+        let variance = sfx.pitch_variance();
+        let speed = if variance > 0.0 {
+            rng.random_range(1.0 - variance..=1.0 + variance)
+        } else {
+            1.0
+        };
+
+        let mut playback = PlaybackSettings {
+            mode: PlaybackMode::Despawn,
+            volume: volume(&settings, sfx),
+            speed,
+            ..default()
+        };
+        if pos.is_some() {
+            playback = playback
+                .with_spatial(true)
+                .with_spatial_scale(SPATIAL_SCALE);
+        }
+
+        let ent = commands
+            .spawn((
+                AudioPlayer::new(sfx.handle(&game_assets)),
+                playback,
+                Transform::from_translation(pos.unwrap_or_default().extend(0.0)),
+                SoundEffect {
+                    sfx,
+                    started: time.elapsed_secs(),
+                },
+            ))
+            .id();
+        voices.push((ent, sfx, time.elapsed_secs()));
+    }
+}
+
+fn volume(settings: &Settings, sfx: Sfx) -> Volume {
+    Volume::Linear(settings.sfx_volume().to_linear() * sfx.volume())
+}
+
+// so a long sound follows the slider
+fn set_sfx_volume(
+    settings: Res<Settings>,
+    mut q_voices: Query<(&SoundEffect, &mut AudioSink)>,
+    mut q_spatial_voices: Query<(&SoundEffect, &mut SpatialAudioSink)>,
+) {
+    for (voice, mut sink) in &mut q_voices {
+        sink.set_volume(volume(&settings, voice.sfx));
+    }
+    for (voice, mut sink) in &mut q_spatial_voices {
+        sink.set_volume(volume(&settings, voice.sfx));
+    }
+}
@@ -0,0 +1,138 @@
+use bevy::log::{error, warn};
+use serde::{de::DeserializeOwned, Serialize};
+
+// small key/value store for anything that should survive a restart,
+// RON files in the user's data directory on native and localStorage
+// on the web. errors are logged and otherwise ignored, losing a save
+// shouldn't stop the game
+
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+fn path(key: &str) -> Option<std::path::PathBuf> {
+    Some(dirs::data_dir()?.join("bosconian").join(format!("{}.ron", key)))
+}
+
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+fn read(key: &str) -> Option<String> {
+    std::fs::read_to_string(path(key)?).ok()
+}
+
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+fn write(key: &str, value: &str) {
+    let Some(path) = path(key) else {
+        warn!("No data directory, {} won't be saved", key);
+        return;
+    };
+
+    let result = path
+        .parent()
+        .map_or(Ok(()), std::fs::create_dir_all)
+        .and_then(|_| std::fs::write(&path, value));
+    if let Err(err) = result {
+        error!("Unable to save {}: {}", path.display(), err);
+    }
+}
+
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+fn local_storage() -> Option<web_sys::Storage> {
+    web_sys::window()?.local_storage().ok()?
+}
+
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+fn read(key: &str) -> Option<String> {
+    local_storage()?
+        .get_item(&format!("bosconian.{}", key))
+        .ok()?
+}
+
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+fn write(key: &str, value: &str) {
+    let saved = local_storage()
+        .map(|storage| storage.set_item(&format!("bosconian.{}", key), value).is_ok());
+    if saved != Some(true) {
+        error!("Unable to save {} to localStorage", key);
+    }
+}
+
+pub fn load<T: DeserializeOwned>(key: &str) -> Option<T> {
+    let text = read(key)?;
+    match ron::from_str(&text) {
+        Ok(value) => Some(value),
+        Err(err) => {
+            warn!("Ignoring saved {}: {}", key, err);
+            None
+        }
+    }
+}
+
+pub fn save<T: Serialize>(key: &str, value: &T) {
+    match ron::ser::to_string_pretty(value, Default::default()) {
+        Ok(text) => write(key, &text),
+        Err(err) => error!("Unable to save {}: {}", key, err),
+    }
+}
+
+// today's date as YYYY-MM-DD
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+pub fn today() -> String {
+    let secs = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+    date_from_days((secs / 86400) as i64)
+}
+
+// days since 1970-01-01 to a civil date, see
+// http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+fn date_from_days(days: i64) -> String {
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+pub fn today() -> String {
+    let date = js_sys::Date::new_0();
+    format!(
+        "{:04}-{:02}-{:02}",
+        date.get_full_year(),
+        date.get_month() + 1,
+        date.get_date()
+    )
+}
+
+#[cfg(all(test, not(all(target_arch = "wasm32", target_os = "unknown"))))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn date_from_days_counts_from_1970() {
+        assert_eq!(date_from_days(0), "1970-01-01");
+        assert_eq!(date_from_days(10956), "1999-12-31");
+        assert_eq!(date_from_days(20744), "2026-10-18");
+    }
+
+    #[test]
+    fn date_from_days_handles_leap_years() {
+        assert_eq!(date_from_days(11016), "2000-02-29");
+        assert_eq!(date_from_days(11017), "2000-03-01");
+        // 2100 isn't a leap year
+        assert_eq!(date_from_days(47540), "2100-02-28");
+        assert_eq!(date_from_days(47541), "2100-03-01");
+    }
+
+    #[test]
+    fn today_is_a_date() {
+        let today = today();
+        assert_eq!(today.len(), 10);
+        assert_eq!(today.as_bytes()[4], b'-');
+        assert_eq!(today.as_bytes()[7], b'-');
+    }
+}